@@ -0,0 +1,136 @@
+use std::time::Instant;
+
+pub(crate) struct Deadline<K> {
+    pub(crate) at: Instant,
+    pub(crate) seq: u64,
+    pub(crate) key: K,
+}
+
+impl<K> Deadline<K> {
+    fn precedes(&self, other: &Self) -> bool {
+        (self.at, self.seq) < (other.at, other.seq)
+    }
+}
+
+pub(crate) struct DeadlineHeap<K> {
+    items: Vec<Deadline<K>>,
+}
+
+impl<K> DeadlineHeap<K> {
+    pub(crate) fn new() -> Self {
+        Self { items: Vec::new() }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub(crate) fn peek(&self) -> Option<&Deadline<K>> {
+        self.items.first()
+    }
+
+    pub(crate) fn push(&mut self, deadline: Deadline<K>) {
+        self.items.push(deadline);
+        self.sift_up(self.items.len() - 1);
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<Deadline<K>> {
+        if self.items.is_empty() {
+            return None;
+        }
+
+        let top = self.items.swap_remove(0);
+        if !self.items.is_empty() {
+            self.sift_down(0);
+        }
+        Some(top)
+    }
+
+    pub(crate) fn retain<F>(&mut self, f: F)
+    where
+        F: FnMut(&Deadline<K>) -> bool,
+    {
+        self.items.retain(f);
+        for i in (0..self.items.len() / 2).rev() {
+            self.sift_down(i);
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if !self.items[i].precedes(&self.items[parent]) {
+                break;
+            }
+            self.items.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        let len = self.items.len();
+        loop {
+            let mut first = i;
+            for child in [2 * i + 1, 2 * i + 2] {
+                if child < len && self.items[child].precedes(&self.items[first]) {
+                    first = child;
+                }
+            }
+            if first == i {
+                break;
+            }
+            self.items.swap(i, first);
+            i = first;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Deadline, DeadlineHeap};
+
+    fn heap_of(offsets: &[u64]) -> (DeadlineHeap<usize>, Instant) {
+        let start = Instant::now();
+        let mut heap = DeadlineHeap::new();
+        for (seq, &secs) in offsets.iter().enumerate() {
+            heap.push(Deadline {
+                at: start + Duration::from_secs(secs),
+                seq: seq as u64,
+                key: seq,
+            });
+        }
+        (heap, start)
+    }
+
+    fn drain_keys(heap: &mut DeadlineHeap<usize>) -> Vec<usize> {
+        std::iter::from_fn(|| heap.pop().map(|d| d.key)).collect()
+    }
+
+    #[test]
+    fn pops_by_deadline_then_sequence() {
+        let (mut heap, start) = heap_of(&[5, 1, 9, 1, 3, 7, 0, 5]);
+        assert_eq!(heap.len(), 8);
+        assert_eq!(heap.peek().map(|d| d.at), Some(start));
+
+        assert_eq!(drain_keys(&mut heap), [6, 1, 3, 4, 0, 7, 5, 2]);
+        assert!(heap.pop().is_none());
+    }
+
+    #[test]
+    fn retain_restores_heap_order() {
+        let offsets: Vec<u64> = (0..64).map(|i| (i * 37) % 64).collect();
+        let (mut heap, _) = heap_of(&offsets);
+
+        heap.retain(|d| d.key % 3 != 0);
+
+        let mut expected: Vec<usize> = (0..64).filter(|k| k % 3 != 0).collect();
+        expected.sort_by_key(|&k| offsets[k]);
+        assert_eq!(drain_keys(&mut heap), expected);
+    }
+}
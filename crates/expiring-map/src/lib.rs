@@ -0,0 +1,442 @@
+mod clock;
+mod heap;
+
+pub use clock::{Clock, ManualClock, SystemClock};
+
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+use hash_map::HashMap;
+
+use heap::{Deadline, DeadlineHeap};
+
+struct Entry<V> {
+    value: V,
+    deadline: Option<Instant>,
+    seq: u64,
+}
+
+impl<V> Entry<V> {
+    fn is_live(&self, now: Instant) -> bool {
+        self.deadline.is_none_or(|deadline| deadline > now)
+    }
+}
+
+pub struct ExpiringMap<K, V, C = SystemClock> {
+    map: HashMap<K, Entry<V>>,
+    expiry: DeadlineHeap<K>,
+    stale: usize,
+    default_ttl: Duration,
+    clock: C,
+    next_seq: u64,
+}
+
+impl<K: Eq + Hash + Clone, V> ExpiringMap<K, V> {
+    pub fn new(default_ttl: Duration) -> Self {
+        Self::with_clock(default_ttl, SystemClock)
+    }
+}
+
+impl<K: Eq + Hash + Clone, V, C: Clock> ExpiringMap<K, V, C> {
+    pub fn with_clock(default_ttl: Duration, clock: C) -> Self {
+        Self {
+            map: HashMap::new(),
+            expiry: DeadlineHeap::new(),
+            stale: 0,
+            default_ttl,
+            clock,
+            next_seq: 0,
+        }
+    }
+
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    pub fn default_ttl(&self) -> Duration {
+        self.default_ttl
+    }
+
+    pub fn len(&mut self) -> usize {
+        self.purge_expired();
+        self.map.len()
+    }
+
+    pub fn is_empty(&mut self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert_with_ttl(key, value, self.default_ttl)
+    }
+
+    pub fn insert_with_ttl(&mut self, key: K, value: V, ttl: Duration) -> Option<V> {
+        let now = self.clock.now();
+        let deadline = now.checked_add(ttl);
+        let seq = self.next_seq;
+        self.next_seq += 1;
+
+        if let Some(at) = deadline {
+            self.expiry.push(Deadline {
+                at,
+                seq,
+                key: key.clone(),
+            });
+        }
+        let old = self.map.insert(
+            key,
+            Entry {
+                value,
+                deadline,
+                seq,
+            },
+        )?;
+
+        self.unindex(&old);
+        old.is_live(now).then_some(old.value)
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.peek(key).is_some()
+    }
+
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let entry = self.map.get(key)?;
+        entry.is_live(self.clock.now()).then_some(&entry.value)
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.purge_if_expired(key);
+        self.map.get(key).map(|entry| &entry.value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.purge_if_expired(key);
+        self.map.get_mut(key).map(|entry| &mut entry.value)
+    }
+
+    pub fn ttl<Q>(&self, key: &Q) -> Option<Duration>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let entry = self.map.get(key)?;
+        let Some(deadline) = entry.deadline else {
+            return Some(Duration::MAX);
+        };
+        let remaining = deadline.checked_duration_since(self.clock.now())?;
+        (!remaining.is_zero()).then_some(remaining)
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let entry = self.map.remove(key)?;
+        self.unindex(&entry);
+        entry.is_live(self.clock.now()).then_some(entry.value)
+    }
+
+    pub fn purge_expired(&mut self) -> usize {
+        let now = self.clock.now();
+        let mut purged = 0;
+
+        while self.expiry.peek().is_some_and(|next| next.at <= now) {
+            let Deadline { seq, key, .. } = self.expiry.pop().expect("peeked deadline");
+            if self.map.get(&key).is_some_and(|entry| entry.seq == seq) {
+                self.map.remove(&key);
+                purged += 1;
+            } else {
+                self.stale -= 1;
+            }
+        }
+
+        purged
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.expiry.clear();
+        self.stale = 0;
+    }
+
+    fn purge_if_expired<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let now = self.clock.now();
+        let expired = match self.map.get(key) {
+            Some(entry) => !entry.is_live(now),
+            None => false,
+        };
+
+        if expired {
+            self.remove(key);
+        }
+    }
+
+    fn unindex(&mut self, entry: &Entry<V>) {
+        if entry.deadline.is_none() {
+            return;
+        }
+
+        self.stale += 1;
+        if self.stale * 2 > self.expiry.len() {
+            let map = &self.map;
+            self.expiry
+                .retain(|d| map.get(&d.key).is_some_and(|entry| entry.seq == d.seq));
+            self.stale = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{ExpiringMap, ManualClock};
+
+    fn secs(s: u64) -> Duration {
+        Duration::from_secs(s)
+    }
+
+    fn map(ttl: u64) -> (ExpiringMap<String, i32, ManualClock>, ManualClock) {
+        let clock = ManualClock::new();
+        (ExpiringMap::with_clock(secs(ttl), clock.clone()), clock)
+    }
+
+    #[test]
+    fn new_map_is_empty() {
+        let (mut m, _) = map(10);
+        assert!(m.is_empty());
+        assert_eq!(m.default_ttl(), secs(10));
+    }
+
+    #[test]
+    fn entry_is_visible_before_deadline() {
+        let (mut m, clock) = map(10);
+        m.insert("a".to_string(), 1);
+
+        clock.advance(secs(9));
+        assert_eq!(m.get("a"), Some(&1));
+        assert!(m.contains("a"));
+        assert_eq!(m.ttl("a"), Some(secs(1)));
+    }
+
+    #[test]
+    fn get_treats_expired_entry_as_absent_and_purges_it() {
+        let (mut m, clock) = map(10);
+        m.insert("a".to_string(), 1);
+
+        clock.advance(secs(10));
+        assert_eq!(m.peek("a"), None);
+        assert!(!m.contains("a"));
+        assert_eq!(m.map.len(), 1);
+
+        assert_eq!(m.get("a"), None);
+        assert_eq!(m.map.len(), 0);
+        assert_eq!(m.ttl("a"), None);
+    }
+
+    #[test]
+    fn len_never_counts_expired_entries() {
+        let (mut m, clock) = map(10);
+        m.insert("a".to_string(), 1);
+        m.insert_with_ttl("b".to_string(), 2, secs(20));
+
+        clock.advance(secs(10));
+        assert!(!m.contains("a"));
+        assert_eq!(m.len(), 1);
+        assert!(!m.is_empty());
+
+        clock.advance(secs(10));
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn insert_with_ttl_overrides_default() {
+        let (mut m, clock) = map(10);
+        m.insert_with_ttl("short".to_string(), 1, secs(2));
+        m.insert("long".to_string(), 2);
+
+        clock.advance(secs(3));
+        assert_eq!(m.get("short"), None);
+        assert_eq!(m.get("long"), Some(&2));
+    }
+
+    #[test]
+    fn reinsert_resets_deadline_and_returns_live_value() {
+        let (mut m, clock) = map(10);
+        m.insert("a".to_string(), 1);
+
+        clock.advance(secs(8));
+        assert_eq!(m.insert("a".to_string(), 2), Some(1));
+
+        clock.advance(secs(8));
+        assert_eq!(m.get("a"), Some(&2));
+        assert_eq!(m.purge_expired(), 0);
+    }
+
+    #[test]
+    fn reinsert_after_expiry_does_not_return_stale_value() {
+        let (mut m, clock) = map(10);
+        m.insert("a".to_string(), 1);
+
+        clock.advance(secs(11));
+        assert_eq!(m.insert("a".to_string(), 2), None);
+        assert_eq!(m.get("a"), Some(&2));
+    }
+
+    #[test]
+    fn get_mut_updates_live_entry() {
+        let (mut m, clock) = map(10);
+        m.insert("a".to_string(), 1);
+
+        *m.get_mut("a").unwrap() += 1;
+        assert_eq!(m.peek("a"), Some(&2));
+
+        clock.advance(secs(10));
+        assert!(m.get_mut("a").is_none());
+    }
+
+    #[test]
+    fn remove_returns_only_live_values() {
+        let (mut m, clock) = map(10);
+        m.insert("a".to_string(), 1);
+        m.insert_with_ttl("b".to_string(), 2, secs(1));
+
+        clock.advance(secs(1));
+        assert_eq!(m.remove("a"), Some(1));
+        assert_eq!(m.remove("b"), None);
+        assert!(m.is_empty());
+        assert_eq!(m.purge_expired(), 0);
+    }
+
+    #[test]
+    fn purge_expired_sweeps_in_deadline_order() {
+        let (mut m, clock) = map(100);
+        for i in 0..10 {
+            m.insert_with_ttl(format!("k{i}"), i, secs(i as u64 + 1));
+        }
+
+        clock.advance(secs(4));
+        assert_eq!(m.purge_expired(), 4);
+        assert_eq!(m.len(), 6);
+        assert!(!m.contains("k3"));
+        assert!(m.contains("k4"));
+
+        clock.advance(secs(100));
+        assert_eq!(m.purge_expired(), 6);
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn purge_ignores_overwritten_deadlines() {
+        let (mut m, clock) = map(10);
+        m.insert_with_ttl("a".to_string(), 1, secs(1));
+        m.insert_with_ttl("a".to_string(), 2, secs(20));
+
+        clock.advance(secs(5));
+        assert_eq!(m.purge_expired(), 0);
+        assert_eq!(m.get("a"), Some(&2));
+    }
+
+    #[test]
+    fn overwrites_and_removals_keep_the_index_compact() {
+        let (mut m, clock) = map(10);
+        for i in 0..1000 {
+            m.insert("hot".to_string(), i);
+            assert!(m.expiry.len() <= 3);
+        }
+
+        for i in 0..100 {
+            m.insert(format!("k{i}"), i);
+        }
+        for i in 0..90 {
+            m.remove(format!("k{i}").as_str());
+        }
+        assert!(m.expiry.len() <= 2 * m.len());
+
+        clock.advance(secs(10));
+        assert_eq!(m.purge_expired(), 11);
+        assert_eq!(m.expiry.len(), 0);
+        assert_eq!(m.stale, 0);
+    }
+
+    #[test]
+    fn zero_ttl_expires_immediately() {
+        let (mut m, _) = map(10);
+        m.insert_with_ttl("a".to_string(), 1, Duration::ZERO);
+        assert_eq!(m.get("a"), None);
+    }
+
+    #[test]
+    fn unbounded_ttl_never_expires() {
+        let (mut m, clock) = map(10);
+        m.insert_with_ttl("forever".to_string(), 1, Duration::MAX);
+        m.insert("brief".to_string(), 2);
+
+        clock.advance(secs(u32::MAX as u64));
+        assert_eq!(m.purge_expired(), 1);
+        assert_eq!(m.ttl("forever"), Some(Duration::MAX));
+        assert_eq!(m.get("forever"), Some(&1));
+
+        assert_eq!(m.insert("forever".to_string(), 3), Some(1));
+        assert_eq!(m.ttl("forever"), Some(secs(10)));
+        assert_eq!(m.remove("forever"), Some(3));
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn session_cache_expires_idle_tokens() {
+        let (mut sessions, clock) = map(30 * 60);
+        sessions.insert("alice".to_string(), 1);
+        sessions.insert("bob".to_string(), 2);
+
+        clock.advance(secs(20 * 60));
+        let token = *sessions.get("alice").unwrap();
+        sessions.insert("alice".to_string(), token);
+
+        clock.advance(secs(15 * 60));
+        assert_eq!(sessions.purge_expired(), 1);
+        assert!(sessions.contains("alice"));
+        assert!(!sessions.contains("bob"));
+    }
+
+    #[test]
+    fn clear_drops_entries_and_index() {
+        let (mut m, clock) = map(10);
+        m.insert("a".to_string(), 1);
+        m.clear();
+
+        clock.advance(secs(20));
+        assert_eq!(m.purge_expired(), 0);
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn system_clock_map_keeps_fresh_entries() {
+        let mut m = ExpiringMap::new(secs(60));
+        m.insert(1, "one");
+        assert_eq!(m.get(&1), Some(&"one"));
+    }
+}
@@ -0,0 +1,420 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+use std::sync::Arc;
+
+use crate::{DefaultHashBuilder, HashMap, RandomState};
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Overwritten<L, R> {
+    Neither,
+    Left(L, R),
+    Right(L, R),
+    Pair(L, R),
+    Both((L, R), (L, R)),
+}
+
+pub struct BiMap<L, R, S = DefaultHashBuilder> {
+    left: HashMap<Arc<L>, Arc<R>, S>,
+    right: HashMap<Arc<R>, Arc<L>, S>,
+}
+
+pub struct Iter<'a, L, R> {
+    inner: crate::Iter<'a, Arc<L>, Arc<R>>,
+}
+
+impl<L: Eq + Hash, R: Eq + Hash, S: BuildHasher + Clone + Default> Default for BiMap<L, R, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<L: Eq + Hash, R: Eq + Hash> BiMap<L, R> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_hasher(RandomState::with_seed(seed))
+    }
+}
+
+impl<L: Eq + Hash, R: Eq + Hash, S: BuildHasher + Clone> BiMap<L, R, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            left: HashMap::with_hasher(hash_builder.clone()),
+            right: HashMap::with_hasher(hash_builder),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.left.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.left.is_empty()
+    }
+
+    pub fn insert(&mut self, left: L, right: R) -> Overwritten<L, R> {
+        let by_left = self.remove_by_left(&left);
+        let by_right = self.remove_by_right(&right);
+
+        let overwritten = match (by_left, by_right) {
+            (None, None) => Overwritten::Neither,
+            (Some((l, r)), None) if r == right => Overwritten::Pair(l, r),
+            (Some((l, r)), None) => Overwritten::Left(l, r),
+            (None, Some((l, r))) => Overwritten::Right(l, r),
+            (Some(a), Some(b)) => Overwritten::Both(a, b),
+        };
+
+        self.link(left, right);
+        overwritten
+    }
+
+    pub fn try_insert(&mut self, left: L, right: R) -> Result<(), (L, R)> {
+        if self.contains_left(&left) || self.contains_right(&right) {
+            return Err((left, right));
+        }
+
+        self.link(left, right);
+        Ok(())
+    }
+
+    pub fn contains_left<Q>(&self, left: &Q) -> bool
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.left.locate_by(left, shared_eq(left)).is_some()
+    }
+
+    pub fn contains_right<Q>(&self, right: &Q) -> bool
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.right.locate_by(right, shared_eq(right)).is_some()
+    }
+
+    pub fn get_by_left<Q>(&self, left: &Q) -> Option<&R>
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let location = self.left.locate_by(left, shared_eq(left))?;
+        Some(&**self.left.pair_at(location).1)
+    }
+
+    pub fn get_by_right<Q>(&self, right: &Q) -> Option<&L>
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let location = self.right.locate_by(right, shared_eq(right))?;
+        Some(&**self.right.pair_at(location).1)
+    }
+
+    pub fn remove_by_left<Q>(&mut self, left: &Q) -> Option<(L, R)>
+    where
+        L: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (l, shared_r) = self.left.remove_entry_by(left, shared_eq(left))?;
+        let (r, shared_l) = self
+            .right
+            .remove_entry(&*shared_r)
+            .expect("right index must mirror left index");
+        drop((shared_l, shared_r));
+        Some(Self::unwrap_pair(l, r))
+    }
+
+    pub fn remove_by_right<Q>(&mut self, right: &Q) -> Option<(L, R)>
+    where
+        R: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (r, shared_l) = self.right.remove_entry_by(right, shared_eq(right))?;
+        let (l, shared_r) = self
+            .left
+            .remove_entry(&*shared_l)
+            .expect("left index must mirror right index");
+        drop((shared_l, shared_r));
+        Some(Self::unwrap_pair(l, r))
+    }
+
+    pub fn clear(&mut self) {
+        self.left.clear();
+        self.right.clear();
+    }
+
+    fn link(&mut self, left: L, right: R) {
+        let (left, right) = (Arc::new(left), Arc::new(right));
+        self.left.insert(Arc::clone(&left), Arc::clone(&right));
+        self.right.insert(right, left);
+    }
+
+    fn unwrap_pair(left: Arc<L>, right: Arc<R>) -> (L, R) {
+        let left = Arc::into_inner(left).expect("left value must not be shared");
+        let right = Arc::into_inner(right).expect("right value must not be shared");
+        (left, right)
+    }
+}
+
+fn shared_eq<T, Q>(key: &Q) -> impl Fn(&Arc<T>) -> bool + '_
+where
+    T: Borrow<Q>,
+    Q: ?Sized + Eq,
+{
+    move |shared| (**shared).borrow() == key
+}
+
+impl<L, R, S> BiMap<L, R, S> {
+    pub fn iter(&self) -> Iter<'_, L, R> {
+        Iter {
+            inner: self.left.iter(),
+        }
+    }
+}
+
+impl<L: fmt::Debug, R: fmt::Debug, S> fmt::Debug for BiMap<L, R, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<L, R, S> FromIterator<(L, R)> for BiMap<L, R, S>
+where
+    L: Eq + Hash,
+    R: Eq + Hash,
+    S: BuildHasher + Clone + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (L, R)>>(iter: I) -> Self {
+        let mut map = Self::with_hasher(S::default());
+        map.extend(iter);
+        map
+    }
+}
+
+impl<L: Eq + Hash, R: Eq + Hash, S: BuildHasher + Clone> Extend<(L, R)> for BiMap<L, R, S> {
+    fn extend<I: IntoIterator<Item = (L, R)>>(&mut self, iter: I) {
+        for (l, r) in iter {
+            self.insert(l, r);
+        }
+    }
+}
+
+impl<'a, L, R, S> IntoIterator for &'a BiMap<L, R, S> {
+    type Item = (&'a L, &'a R);
+    type IntoIter = Iter<'a, L, R>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, L, R> Iterator for Iter<'a, L, R> {
+    type Item = (&'a L, &'a R);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(l, r)| (&**l, &**r))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<L, R> ExactSizeIterator for Iter<'_, L, R> {}
+
+impl<L, R> FusedIterator for Iter<'_, L, R> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{BiMap, Overwritten};
+
+    fn assert_consistent(m: &BiMap<u32, String>) {
+        assert_eq!(m.left.len(), m.right.len());
+        for (l, r) in m.iter() {
+            assert_eq!(m.get_by_left(l), Some(r));
+            assert_eq!(m.get_by_right(r), Some(l));
+        }
+    }
+
+    fn users() -> BiMap<u32, String> {
+        let mut m = BiMap::new();
+        m.insert(1, "ana".to_string());
+        m.insert(2, "bia".to_string());
+        m.insert(3, "caio".to_string());
+        m
+    }
+
+    #[test]
+    fn new_map_is_empty() {
+        let m = BiMap::<u32, String>::new();
+        assert!(m.is_empty());
+        assert_eq!(m.len(), 0);
+    }
+
+    #[test]
+    fn lookups_work_in_both_directions() {
+        let m = users();
+        assert_eq!(m.len(), 3);
+        assert_eq!(m.get_by_left(&2), Some(&"bia".to_string()));
+        assert_eq!(m.get_by_right("caio"), Some(&3));
+        assert_eq!(m.get_by_left(&9), None);
+        assert!(m.contains_left(&1));
+        assert!(m.contains_right("ana"));
+        assert_consistent(&m);
+    }
+
+    #[test]
+    fn lookups_accept_borrowed_forms_on_both_sides() {
+        let mut m: BiMap<String, Vec<u8>> = BiMap::new();
+        m.insert("ana".to_string(), vec![1, 2]);
+
+        assert_eq!(m.get_by_left("ana"), Some(&vec![1, 2]));
+        assert_eq!(m.get_by_right(&[1, 2][..]), Some(&"ana".to_string()));
+        assert!(m.contains_right(&[1u8, 2][..]));
+        assert_eq!(
+            m.remove_by_left("ana"),
+            Some(("ana".to_string(), vec![1, 2]))
+        );
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn bi_map_can_move_across_threads() {
+        let m = users();
+        let handle = std::thread::spawn(move || m.get_by_left(&1).cloned());
+        assert_eq!(handle.join().unwrap(), Some("ana".to_string()));
+    }
+
+    #[test]
+    fn insert_new_pair_overwrites_neither() {
+        let mut m = users();
+        assert_eq!(m.insert(4, "duda".to_string()), Overwritten::Neither);
+        assert_consistent(&m);
+    }
+
+    #[test]
+    fn insert_existing_left_overwrites_left_pair() {
+        let mut m = users();
+        assert_eq!(
+            m.insert(1, "alice".to_string()),
+            Overwritten::Left(1, "ana".to_string())
+        );
+        assert!(!m.contains_right("ana"));
+        assert_eq!(m.len(), 3);
+        assert_consistent(&m);
+    }
+
+    #[test]
+    fn insert_existing_right_overwrites_right_pair() {
+        let mut m = users();
+        assert_eq!(
+            m.insert(10, "ana".to_string()),
+            Overwritten::Right(1, "ana".to_string())
+        );
+        assert!(!m.contains_left(&1));
+        assert_eq!(m.get_by_right(&"ana".to_string()), Some(&10));
+        assert_consistent(&m);
+    }
+
+    #[test]
+    fn insert_same_pair_reports_pair() {
+        let mut m = users();
+        assert_eq!(
+            m.insert(2, "bia".to_string()),
+            Overwritten::Pair(2, "bia".to_string())
+        );
+        assert_eq!(m.len(), 3);
+        assert_consistent(&m);
+    }
+
+    #[test]
+    fn insert_crossing_pairs_overwrites_both() {
+        let mut m = users();
+        assert_eq!(
+            m.insert(1, "bia".to_string()),
+            Overwritten::Both((1, "ana".to_string()), (2, "bia".to_string()))
+        );
+        assert_eq!(m.len(), 2);
+        assert!(!m.contains_left(&2));
+        assert!(!m.contains_right("ana"));
+        assert_consistent(&m);
+    }
+
+    #[test]
+    fn try_insert_refuses_to_overwrite() {
+        let mut m = users();
+        assert_eq!(
+            m.try_insert(1, "zeca".to_string()),
+            Err((1, "zeca".to_string()))
+        );
+        assert_eq!(
+            m.try_insert(9, "ana".to_string()),
+            Err((9, "ana".to_string()))
+        );
+        assert_eq!(m.try_insert(9, "zeca".to_string()), Ok(()));
+        assert_eq!(m.len(), 4);
+        assert_consistent(&m);
+    }
+
+    #[test]
+    fn remove_by_left_removes_both_directions() {
+        let mut m = users();
+        assert_eq!(m.remove_by_left(&1), Some((1, "ana".to_string())));
+        assert_eq!(m.remove_by_left(&1), None);
+        assert!(!m.contains_right("ana"));
+        assert_consistent(&m);
+    }
+
+    #[test]
+    fn remove_by_right_removes_both_directions() {
+        let mut m = users();
+        assert_eq!(m.remove_by_right("bia"), Some((2, "bia".to_string())));
+        assert_eq!(m.remove_by_right("bia"), None);
+        assert!(!m.contains_left(&2));
+        assert_consistent(&m);
+    }
+
+    #[test]
+    fn iter_visits_every_pair() {
+        let m = users();
+        let mut pairs: Vec<_> = m.iter().map(|(l, r)| (*l, r.clone())).collect();
+        pairs.sort();
+        assert_eq!(
+            pairs,
+            [
+                (1, "ana".to_string()),
+                (2, "bia".to_string()),
+                (3, "caio".to_string())
+            ]
+        );
+        assert_eq!(m.iter().len(), 3);
+    }
+
+    #[test]
+    fn stays_consistent_under_mixed_operations() {
+        let mut m = BiMap::<u32, String>::with_seed(3);
+        for i in 0..500u32 {
+            m.insert(i % 97, format!("n{}", (i * 7) % 89));
+            if i % 5 == 0 {
+                m.remove_by_left(&(i % 13));
+            }
+            if i % 7 == 0 {
+                m.remove_by_right(&format!("n{}", i % 11));
+            }
+            assert_consistent(&m);
+        }
+    }
+
+    #[test]
+    fn clear_empties_both_indexes() {
+        let mut m = users();
+        m.clear();
+        assert!(m.is_empty());
+        assert_consistent(&m);
+    }
+}
@@ -0,0 +1,594 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::hash::{BuildHasher, Hash};
+use std::sync::OnceLock;
+use std::vec;
+
+use crate::RandomState;
+
+pub(crate) const TREEIFY_THRESHOLD: usize = 8;
+pub(crate) const UNTREEIFY_THRESHOLD: usize = 6;
+
+const NIL: usize = usize::MAX;
+
+static TAG_STATE: OnceLock<RandomState> = OnceLock::new();
+
+pub(crate) type KeyTag<K> = fn(&K) -> u64;
+
+pub(crate) fn key_tag<Q: ?Sized + Hash>(key: &Q) -> u64 {
+    TAG_STATE.get_or_init(RandomState::new).hash_one(key)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Bucket<K, V> {
+    entries: Vec<(K, V)>,
+    hashes: Vec<u64>,
+    tree: Option<Tree>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Tree {
+    root: usize,
+    tags: Vec<u64>,
+    nodes: Vec<Node>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Node {
+    left: usize,
+    right: usize,
+    height: u32,
+}
+
+struct LazyTag<'a, Q: ?Sized> {
+    key: &'a Q,
+    tag: Option<u64>,
+}
+
+impl<Q: ?Sized + Hash> LazyTag<'_, Q> {
+    fn get(&mut self) -> u64 {
+        *self.tag.get_or_insert_with(|| key_tag(self.key))
+    }
+}
+
+impl<K, V> Bucket<K, V> {
+    pub(crate) fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            hashes: Vec::new(),
+            tree: None,
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_ordered(&self) -> bool {
+        self.tree.is_some()
+    }
+
+    pub(crate) fn entries(&self) -> &[(K, V)] {
+        &self.entries
+    }
+
+    pub(crate) fn entries_mut(&mut self) -> &mut [(K, V)] {
+        &mut self.entries
+    }
+
+    pub(crate) fn into_entries(self) -> Vec<(K, V)> {
+        self.entries
+    }
+
+    pub(crate) fn into_parts(self) -> impl Iterator<Item = (u64, (K, V))> {
+        self.hashes.into_iter().zip(self.entries)
+    }
+
+    pub(crate) fn find<Q>(&self, hash: u64, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.find_by(hash, key, |k| k.borrow() == key)
+    }
+
+    pub(crate) fn find_by<Q, F>(&self, hash: u64, key: &Q, eq: F) -> Option<usize>
+    where
+        Q: ?Sized + Hash,
+        F: Fn(&K) -> bool,
+    {
+        self.probe(hash, key, &eq).0
+    }
+
+    #[cfg(test)]
+    pub(crate) fn probes<Q>(&self, hash: u64, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.probe(hash, key, &|k: &K| k.borrow() == key).1
+    }
+
+    fn probe<Q, F>(&self, hash: u64, key: &Q, eq: &F) -> (Option<usize>, usize)
+    where
+        Q: ?Sized + Hash,
+        F: Fn(&K) -> bool,
+    {
+        let Some(tree) = &self.tree else {
+            for pos in 0..self.entries.len() {
+                if self.hashes[pos] == hash && eq(&self.entries[pos].0) {
+                    return (Some(pos), pos + 1);
+                }
+            }
+            return (None, self.entries.len());
+        };
+
+        let mut probes = 0;
+        let mut tag = LazyTag { key, tag: None };
+        let found = self.search(tree, tree.root, hash, &mut tag, eq, &mut probes);
+        (found, probes)
+    }
+
+    fn search<Q, F>(
+        &self,
+        tree: &Tree,
+        mut n: usize,
+        hash: u64,
+        tag: &mut LazyTag<'_, Q>,
+        eq: &F,
+        probes: &mut usize,
+    ) -> Option<usize>
+    where
+        Q: ?Sized + Hash,
+        F: Fn(&K) -> bool,
+    {
+        while n != NIL {
+            *probes += 1;
+            let node = tree.nodes[n];
+            let ordering = hash
+                .cmp(&self.hashes[n])
+                .then_with(|| tag.get().cmp(&tree.tags[n]));
+
+            match ordering {
+                Ordering::Less => n = node.left,
+                Ordering::Greater => n = node.right,
+                Ordering::Equal if eq(&self.entries[n].0) => return Some(n),
+                Ordering::Equal => {
+                    return self
+                        .search(tree, node.left, hash, tag, eq, probes)
+                        .or_else(|| self.search(tree, node.right, hash, tag, eq, probes));
+                }
+            }
+        }
+
+        None
+    }
+
+    pub(crate) fn insert(&mut self, hash: u64, key: K, value: V, tag: KeyTag<K>) -> usize {
+        let pos = self.entries.len();
+        if let Some(tree) = &mut self.tree {
+            tree.tags.push(tag(&key));
+            tree.nodes.push(Node::leaf());
+        }
+        self.hashes.push(hash);
+        self.entries.push((key, value));
+
+        match &mut self.tree {
+            Some(tree) => tree.root = tree.attach(&self.hashes, tree.root, pos),
+            None if self.entries.len() >= TREEIFY_THRESHOLD => {
+                let tags = self.entries.iter().map(|(k, _)| tag(k)).collect();
+                self.tree = Some(Tree::build(&self.hashes, tags));
+            }
+            None => {}
+        }
+        pos
+    }
+
+    pub(crate) fn remove(&mut self, pos: usize) -> (K, V) {
+        if let Some(tree) = &mut self.tree {
+            tree.root = tree
+                .detach(&self.hashes, tree.root, pos)
+                .expect("every entry of an ordered bucket is linked into its tree");
+            let last = self.entries.len() - 1;
+            if pos != last {
+                tree.relink(&self.hashes, last, pos);
+            }
+            tree.tags.swap_remove(pos);
+            tree.nodes.swap_remove(pos);
+        }
+        self.hashes.swap_remove(pos);
+        let pair = self.entries.swap_remove(pos);
+
+        if self.entries.len() <= UNTREEIFY_THRESHOLD {
+            self.tree = None;
+        }
+        pair
+    }
+
+    pub(crate) fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        let mut keep = Vec::with_capacity(self.entries.len());
+        self.entries.retain_mut(|(k, v)| {
+            let kept = f(k, v);
+            keep.push(kept);
+            kept
+        });
+
+        let mut kept = keep.iter().copied();
+        self.hashes.retain(|_| kept.next().unwrap_or(false));
+
+        self.tree = match self.tree.take() {
+            Some(mut tree) if self.entries.len() > UNTREEIFY_THRESHOLD => {
+                let mut kept = keep.iter().copied();
+                tree.tags.retain(|_| kept.next().unwrap_or(false));
+                Some(Tree::build(&self.hashes, tree.tags))
+            }
+            _ => None,
+        };
+    }
+
+    pub(crate) fn drain(&mut self) -> vec::Drain<'_, (K, V)> {
+        self.hashes.clear();
+        self.tree = None;
+        self.entries.drain(..)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.hashes.clear();
+        self.tree = None;
+    }
+}
+
+impl Node {
+    fn leaf() -> Self {
+        Node {
+            left: NIL,
+            right: NIL,
+            height: 1,
+        }
+    }
+}
+
+impl Tree {
+    fn build(hashes: &[u64], tags: Vec<u64>) -> Self {
+        let mut tree = Tree {
+            root: NIL,
+            nodes: vec![Node::leaf(); tags.len()],
+            tags,
+        };
+        for i in 0..hashes.len() {
+            tree.root = tree.attach(hashes, tree.root, i);
+        }
+        tree
+    }
+
+    fn order(&self, hashes: &[u64], n: usize) -> (u64, u64) {
+        (hashes[n], self.tags[n])
+    }
+
+    fn height(&self, n: usize) -> u32 {
+        if n == NIL { 0 } else { self.nodes[n].height }
+    }
+
+    fn update(&mut self, n: usize) {
+        let Node { left, right, .. } = self.nodes[n];
+        self.nodes[n].height = 1 + self.height(left).max(self.height(right));
+    }
+
+    fn rotate_left(&mut self, n: usize) -> usize {
+        let r = self.nodes[n].right;
+        self.nodes[n].right = self.nodes[r].left;
+        self.nodes[r].left = n;
+        self.update(n);
+        self.update(r);
+        r
+    }
+
+    fn rotate_right(&mut self, n: usize) -> usize {
+        let l = self.nodes[n].left;
+        self.nodes[n].left = self.nodes[l].right;
+        self.nodes[l].right = n;
+        self.update(n);
+        self.update(l);
+        l
+    }
+
+    fn balance(&mut self, n: usize) -> usize {
+        self.update(n);
+        let Node { left, right, .. } = self.nodes[n];
+
+        if self.height(left) > self.height(right) + 1 {
+            if self.height(self.nodes[left].left) < self.height(self.nodes[left].right) {
+                self.nodes[n].left = self.rotate_left(left);
+            }
+            return self.rotate_right(n);
+        }
+        if self.height(right) > self.height(left) + 1 {
+            if self.height(self.nodes[right].right) < self.height(self.nodes[right].left) {
+                self.nodes[n].right = self.rotate_right(right);
+            }
+            return self.rotate_left(n);
+        }
+        n
+    }
+
+    fn attach(&mut self, hashes: &[u64], n: usize, i: usize) -> usize {
+        if n == NIL {
+            self.nodes[i] = Node::leaf();
+            return i;
+        }
+
+        if self.order(hashes, i) < self.order(hashes, n) {
+            self.nodes[n].left = self.attach(hashes, self.nodes[n].left, i);
+        } else {
+            self.nodes[n].right = self.attach(hashes, self.nodes[n].right, i);
+        }
+        self.balance(n)
+    }
+
+    fn detach(&mut self, hashes: &[u64], n: usize, i: usize) -> Option<usize> {
+        if n == NIL {
+            return None;
+        }
+
+        let Node { left, right, .. } = self.nodes[n];
+        if n == i {
+            if left == NIL {
+                return Some(right);
+            }
+            if right == NIL {
+                return Some(left);
+            }
+            let (rest, min) = self.detach_min(right);
+            self.nodes[min].left = left;
+            self.nodes[min].right = rest;
+            return Some(self.balance(min));
+        }
+
+        match self.order(hashes, i).cmp(&self.order(hashes, n)) {
+            Ordering::Less => self.nodes[n].left = self.detach(hashes, left, i)?,
+            Ordering::Greater => self.nodes[n].right = self.detach(hashes, right, i)?,
+            Ordering::Equal => match self.detach(hashes, left, i) {
+                Some(l) => self.nodes[n].left = l,
+                None => self.nodes[n].right = self.detach(hashes, right, i)?,
+            },
+        }
+        Some(self.balance(n))
+    }
+
+    fn detach_min(&mut self, n: usize) -> (usize, usize) {
+        let Node { left, right, .. } = self.nodes[n];
+        if left == NIL {
+            return (right, n);
+        }
+
+        let (rest, min) = self.detach_min(left);
+        self.nodes[n].left = rest;
+        (self.balance(n), min)
+    }
+
+    fn relink(&mut self, hashes: &[u64], from: usize, to: usize) {
+        if self.root == from {
+            self.root = to;
+        } else {
+            let relinked = self.relink_below(hashes, self.root, from, to);
+            debug_assert!(relinked, "moved entry must be linked into the tree");
+        }
+    }
+
+    fn relink_below(&mut self, hashes: &[u64], n: usize, from: usize, to: usize) -> bool {
+        if n == NIL {
+            return false;
+        }
+
+        let Node { left, right, .. } = self.nodes[n];
+        if left == from {
+            self.nodes[n].left = to;
+            return true;
+        }
+        if right == from {
+            self.nodes[n].right = to;
+            return true;
+        }
+
+        match self.order(hashes, from).cmp(&self.order(hashes, n)) {
+            Ordering::Less => self.relink_below(hashes, left, from, to),
+            Ordering::Greater => self.relink_below(hashes, right, from, to),
+            Ordering::Equal => {
+                self.relink_below(hashes, left, from, to)
+                    || self.relink_below(hashes, right, from, to)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::Lcg;
+
+    fn push(b: &mut Bucket<u64, u64>, hash: u64, key: u64) -> usize {
+        b.insert(hash, key, key * 10, key_tag::<u64>)
+    }
+
+    fn filled(hashes: &[u64]) -> Bucket<u64, u64> {
+        let mut b = Bucket::new();
+        for &h in hashes {
+            push(&mut b, h, h);
+        }
+        b
+    }
+
+    fn assert_valid_tree(b: &Bucket<u64, u64>) {
+        let tree = b.tree.as_ref().unwrap();
+
+        fn walk(tree: &Tree, hashes: &[u64], n: usize, order: &mut Vec<(u64, u64)>) -> u32 {
+            if n == NIL {
+                return 0;
+            }
+            let Node {
+                left,
+                right,
+                height,
+            } = tree.nodes[n];
+            let l = walk(tree, hashes, left, order);
+            order.push(tree.order(hashes, n));
+            let r = walk(tree, hashes, right, order);
+            assert!(l.abs_diff(r) <= 1, "unbalanced node {n}");
+            assert_eq!(height, 1 + l.max(r));
+            height
+        }
+
+        let mut order = Vec::new();
+        walk(tree, &b.hashes, tree.root, &mut order);
+        assert_eq!(order.len(), b.len());
+        assert!(order.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(tree.tags.len(), b.len());
+        assert_eq!(tree.nodes.len(), b.len());
+    }
+
+    #[test]
+    fn short_bucket_stays_unordered() {
+        let b = filled(&[5, 3, 1]);
+        assert!(!b.is_ordered());
+        assert_eq!(b.entries(), &[(5, 50), (3, 30), (1, 10)]);
+    }
+
+    #[test]
+    fn bucket_becomes_ordered_at_threshold() {
+        let b = filled(&[9, 8, 7, 6, 5, 4, 3, 2]);
+        assert!(b.is_ordered());
+        assert_valid_tree(&b);
+        assert_eq!(b.len(), TREEIFY_THRESHOLD);
+    }
+
+    #[test]
+    fn insert_returns_position_of_new_pair() {
+        let mut b = filled(&[9, 8, 7, 6, 5, 4, 3]);
+        let pos = push(&mut b, 1, 1);
+        assert_eq!(b.entries()[pos], (1, 10));
+
+        let pos = push(&mut b, 4, 44);
+        assert_eq!(b.entries()[pos], (44, 440));
+        assert_valid_tree(&b);
+    }
+
+    #[test]
+    fn find_locates_pairs_in_both_modes() {
+        let small = filled(&[3, 1, 2]);
+        assert_eq!(small.find(2, &2), Some(2));
+        assert_eq!(small.find(4, &4), None);
+
+        let big = filled(&(0..20).rev().collect::<Vec<_>>());
+        for h in 0..20 {
+            let pos = big.find(h, &h).unwrap();
+            assert_eq!(big.entries()[pos], (h, h * 10));
+        }
+        assert_eq!(big.find(20, &20), None);
+    }
+
+    #[test]
+    fn find_handles_equal_hashes_with_different_keys() {
+        let mut b = Bucket::new();
+        for k in 0..10u64 {
+            push(&mut b, 7, k);
+        }
+        assert!(b.is_ordered());
+        for k in 0..10u64 {
+            assert_eq!(b.entries()[b.find(7, &k).unwrap()], (k, k * 10));
+        }
+        assert_eq!(b.find(7, &10), None);
+    }
+
+    #[test]
+    fn identical_hashes_are_probed_in_logarithmic_steps() {
+        let mut b = Bucket::new();
+        for k in 0..1024u64 {
+            push(&mut b, 7, k);
+        }
+        assert_valid_tree(&b);
+
+        let worst = (0..1024u64).map(|k| b.probes(7, &k)).max().unwrap();
+        assert!(worst <= 15, "worst hit took {worst} probes");
+
+        let worst = (1024..2048u64).map(|k| b.probes(7, &k)).max().unwrap();
+        assert!(worst <= 15, "worst miss took {worst} probes");
+    }
+
+    #[test]
+    fn tree_stays_balanced_through_removals() {
+        let mut b = Bucket::new();
+        for k in 0..200u64 {
+            push(&mut b, k % 3, k);
+        }
+
+        let mut rng = Lcg::new(11);
+        while b.len() > UNTREEIFY_THRESHOLD + 1 {
+            let pos = rng.below(b.len() as u64) as usize;
+            let (k, v) = b.remove(pos);
+            assert_eq!(v, k * 10);
+            assert_eq!(b.find(k % 3, &k), None);
+            assert_valid_tree(&b);
+        }
+
+        for &(k, v) in b.entries() {
+            assert_eq!(b.entries()[b.find(k % 3, &k).unwrap()], (k, v));
+        }
+    }
+
+    #[test]
+    fn bucket_returns_to_unordered_below_untreeify_threshold() {
+        let mut b = filled(&(0..10).collect::<Vec<_>>());
+        assert!(b.is_ordered());
+
+        while b.len() > UNTREEIFY_THRESHOLD + 1 {
+            b.remove(0);
+            assert!(b.is_ordered());
+        }
+        b.remove(0);
+        assert!(!b.is_ordered());
+        assert_eq!(b.len(), UNTREEIFY_THRESHOLD);
+    }
+
+    #[test]
+    fn retain_keeps_hashes_aligned() {
+        let mut b = filled(&(0..12).collect::<Vec<_>>());
+        b.retain(|k, _| k % 3 == 0);
+
+        assert_eq!(b.len(), 4);
+        assert!(!b.is_ordered());
+        for (pos, (k, _)) in b.entries().iter().enumerate() {
+            assert_eq!(b.hashes[pos], *k);
+        }
+    }
+
+    #[test]
+    fn retain_rebuilds_tree_of_large_bucket() {
+        let mut b = filled(&(0..40).collect::<Vec<_>>());
+        b.retain(|k, _| k % 2 == 0);
+
+        assert_eq!(b.len(), 20);
+        assert_valid_tree(&b);
+        for k in (0..40).step_by(2) {
+            assert!(b.find(k, &k).is_some());
+        }
+    }
+
+    #[test]
+    fn drain_and_clear_reset_bucket() {
+        let mut b = filled(&(0..10).collect::<Vec<_>>());
+        assert_eq!(b.drain().count(), 10);
+        assert_eq!(b.len(), 0);
+        assert!(!b.is_ordered());
+
+        let mut b = filled(&(0..10).collect::<Vec<_>>());
+        b.clear();
+        assert_eq!(b.len(), 0);
+        assert!(b.hashes.is_empty());
+        assert!(!b.is_ordered());
+    }
+}
@@ -0,0 +1,459 @@
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+
+use crate::bucket::{self, Bucket};
+use crate::{HASH_MAP_INITIAL_CAPACITY, HashMap, RandomState, ResizeMode};
+
+pub const DEFAULT_MAX_LOAD_FACTOR: f64 = 0.75;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum GrowthPolicy {
+    #[default]
+    Doubling,
+    OneAndHalf,
+    Prime,
+}
+
+impl GrowthPolicy {
+    fn initial(self) -> usize {
+        match self {
+            GrowthPolicy::Doubling | GrowthPolicy::OneAndHalf => HASH_MAP_INITIAL_CAPACITY,
+            GrowthPolicy::Prime => next_prime(HASH_MAP_INITIAL_CAPACITY),
+        }
+    }
+
+    pub(crate) fn next(self, buckets: usize) -> Option<usize> {
+        match self {
+            GrowthPolicy::Doubling => buckets.checked_mul(2),
+            GrowthPolicy::OneAndHalf => buckets.checked_add(buckets / 2),
+            GrowthPolicy::Prime => buckets.checked_mul(2).map(next_prime),
+        }
+    }
+
+    pub(crate) fn buckets_for(self, capacity: usize, max_load_factor: f64) -> Option<usize> {
+        let mut buckets = self.initial();
+        while pairs_for(buckets, max_load_factor) < capacity {
+            buckets = self.next(buckets)?;
+        }
+        Some(buckets)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TryReserveError {
+    CapacityOverflow,
+    AllocError { buckets: usize },
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TryReserveError::CapacityOverflow => write!(f, "capacity overflow"),
+            TryReserveError::AllocError { buckets } => {
+                write!(f, "memory allocation of {buckets} buckets failed")
+            }
+        }
+    }
+}
+
+impl std::error::Error for TryReserveError {}
+
+pub struct HashMapBuilder<S = RandomState> {
+    capacity: usize,
+    max_load_factor: f64,
+    growth_policy: GrowthPolicy,
+    resize_mode: ResizeMode,
+    hash_builder: S,
+}
+
+impl Default for HashMapBuilder<RandomState> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HashMapBuilder<RandomState> {
+    pub fn new() -> Self {
+        Self {
+            capacity: 0,
+            max_load_factor: DEFAULT_MAX_LOAD_FACTOR,
+            growth_policy: GrowthPolicy::Doubling,
+            resize_mode: ResizeMode::Immediate,
+            hash_builder: RandomState::new(),
+        }
+    }
+}
+
+impl<S> HashMapBuilder<S> {
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    pub fn max_load_factor(mut self, max_load_factor: f64) -> Self {
+        assert!(
+            max_load_factor.is_finite() && max_load_factor > 0.0,
+            "max load factor must be positive and finite"
+        );
+        self.max_load_factor = max_load_factor;
+        self
+    }
+
+    pub fn growth_policy(mut self, growth_policy: GrowthPolicy) -> Self {
+        self.growth_policy = growth_policy;
+        self
+    }
+
+    pub fn resize_mode(mut self, resize_mode: ResizeMode) -> Self {
+        self.resize_mode = resize_mode;
+        self
+    }
+
+    pub fn hasher<T>(self, hash_builder: T) -> HashMapBuilder<T> {
+        HashMapBuilder {
+            capacity: self.capacity,
+            max_load_factor: self.max_load_factor,
+            growth_policy: self.growth_policy,
+            resize_mode: self.resize_mode,
+            hash_builder,
+        }
+    }
+
+    pub fn build<K: Eq + Hash, V>(self) -> HashMap<K, V, S>
+    where
+        S: BuildHasher,
+    {
+        let buckets = self
+            .growth_policy
+            .buckets_for(self.capacity, self.max_load_factor)
+            .expect("capacity overflow");
+
+        let mut map = HashMap {
+            buckets: (0..buckets).map(|_| Bucket::new()).collect(),
+            counter: 0,
+            capacity: buckets,
+            hash_builder: self.hash_builder,
+            resize_mode: ResizeMode::Immediate,
+            migration: None,
+            max_load_factor: self.max_load_factor,
+            growth_policy: self.growth_policy,
+            key_tag: bucket::key_tag::<K>,
+        };
+        map.set_resize_mode(self.resize_mode);
+        map
+    }
+}
+
+impl<K: Eq + Hash, V> HashMap<K, V> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<K, V, S> HashMap<K, V, S> {
+    pub fn capacity(&self) -> usize {
+        pairs_for(self.capacity, self.max_load_factor)
+    }
+
+    pub fn max_load_factor(&self) -> f64 {
+        self.max_load_factor
+    }
+
+    pub fn growth_policy(&self) -> GrowthPolicy {
+        self.growth_policy
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        if let Err(e) = self.try_reserve(additional) {
+            panic!("{e}");
+        }
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        let needed = self
+            .counter
+            .checked_add(additional)
+            .ok_or(TryReserveError::CapacityOverflow)?;
+        if needed <= self.capacity() {
+            return Ok(());
+        }
+
+        let mut buckets = self.capacity;
+        while pairs_for(buckets, self.max_load_factor) < needed {
+            buckets = self
+                .growth_policy
+                .next(buckets)
+                .ok_or(TryReserveError::CapacityOverflow)?;
+        }
+
+        self.rebuild(buckets)
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to(0);
+    }
+
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        let target = self.counter.max(min_capacity);
+        let Some(buckets) = self.growth_policy.buckets_for(target, self.max_load_factor) else {
+            return;
+        };
+
+        if buckets < self.capacity {
+            self.rebuild(buckets)
+                .expect("shrinking never needs more memory than the current table");
+        }
+    }
+
+    fn rebuild(&mut self, buckets: usize) -> Result<(), TryReserveError> {
+        let fits = buckets
+            .checked_mul(size_of::<Bucket<K, V>>())
+            .is_some_and(|bytes| bytes <= isize::MAX as usize);
+        if !fits {
+            return Err(TryReserveError::CapacityOverflow);
+        }
+
+        let mut new_buckets = Vec::new();
+        new_buckets
+            .try_reserve_exact(buckets)
+            .map_err(|_| TryReserveError::AllocError { buckets })?;
+        new_buckets.extend((0..buckets).map(|_| Bucket::new()));
+
+        self.finish_resize();
+        for bucket in std::mem::replace(&mut self.buckets, new_buckets) {
+            for (hash, (k, v)) in bucket.into_parts() {
+                let index = (hash as usize) % buckets;
+                self.buckets[index].insert(hash, k, v, self.key_tag);
+            }
+        }
+        self.capacity = buckets;
+
+        Ok(())
+    }
+}
+
+pub(crate) fn pairs_for(buckets: usize, max_load_factor: f64) -> usize {
+    (buckets as f64 * max_load_factor).ceil() as usize
+}
+
+fn next_prime(n: usize) -> usize {
+    (n.max(2)..)
+        .find(|&candidate| is_prime(candidate))
+        .expect("there is always a larger prime")
+}
+
+fn is_prime(n: usize) -> bool {
+    if n < 2 {
+        return false;
+    }
+    (2..)
+        .take_while(|d| d * d <= n)
+        .all(|d| !n.is_multiple_of(d))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_map_reports_default_capacity_and_load_factor() {
+        let h = HashMap::<u64, u64>::new();
+        assert_eq!(h.capacity(), 12);
+        assert_eq!(h.max_load_factor(), DEFAULT_MAX_LOAD_FACTOR);
+        assert_eq!(h.growth_policy(), GrowthPolicy::Doubling);
+    }
+
+    #[test]
+    fn map_holds_capacity_pairs_without_resizing() {
+        let mut h = HashMap::<u64, u64>::with_capacity(1000);
+        let capacity = h.capacity();
+        let buckets = h.capacity;
+        assert!(capacity >= 1000);
+
+        for i in 0..capacity as u64 {
+            h.insert(i, i);
+        }
+        assert_eq!(h.capacity, buckets);
+
+        h.insert(u64::MAX, 0);
+        assert!(h.capacity > buckets);
+    }
+
+    #[test]
+    fn reserve_grows_once_for_bulk_load() {
+        let mut h = HashMap::<u64, u64>::new();
+        h.insert(1, 1);
+        h.reserve(5000);
+        let buckets = h.capacity;
+        assert!(h.capacity() >= 5001);
+
+        for i in 0..5000 {
+            h.insert(i, i);
+        }
+        assert_eq!(h.capacity, buckets);
+        assert_eq!(h.get(&1), Some(&1));
+    }
+
+    #[test]
+    fn reserve_within_capacity_does_nothing() {
+        let mut h = HashMap::<u64, u64>::new();
+        h.reserve(5);
+        assert_eq!(h.capacity, HASH_MAP_INITIAL_CAPACITY);
+    }
+
+    #[test]
+    fn try_reserve_reports_capacity_overflow() {
+        let mut h = HashMap::<u64, u64>::new();
+        h.insert(1, 1);
+        assert_eq!(
+            h.try_reserve(usize::MAX),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(h.get(&1), Some(&1));
+    }
+
+    #[test]
+    fn try_reserve_reports_table_larger_than_address_space_as_overflow() {
+        let mut h = HashMap::<u64, u64>::new();
+        assert_eq!(
+            h.try_reserve(usize::MAX / 4),
+            Err(TryReserveError::CapacityOverflow)
+        );
+        assert_eq!(h.capacity, HASH_MAP_INITIAL_CAPACITY);
+    }
+
+    #[test]
+    fn try_reserve_reports_allocation_failure() {
+        let mut h = HashMap::<u64, u64>::new();
+        let pairs = isize::MAX as usize / size_of::<Bucket<u64, u64>>() / 8;
+        let result = h.try_reserve(pairs);
+        let Err(TryReserveError::AllocError { buckets }) = result else {
+            panic!("expected an allocation failure, got {result:?}");
+        };
+        assert!(pairs_for(buckets, h.max_load_factor) >= pairs);
+        assert_eq!(h.capacity, HASH_MAP_INITIAL_CAPACITY);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            format!("memory allocation of {buckets} buckets failed")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity overflow")]
+    fn reserve_panics_on_overflow() {
+        let mut h = HashMap::<u64, u64>::new();
+        h.insert(1, 1);
+        h.reserve(usize::MAX);
+    }
+
+    #[test]
+    fn shrink_to_fit_releases_buckets_and_keeps_pairs() {
+        let mut h = HashMap::<u64, u64>::new();
+        for i in 0..10_000 {
+            h.insert(i, i);
+        }
+        h.retain(|k, _| *k < 10);
+
+        h.shrink_to_fit();
+        assert_eq!(h.capacity, HASH_MAP_INITIAL_CAPACITY);
+        assert_eq!(h.len(), 10);
+        for i in 0..10 {
+            assert_eq!(h.get(&i), Some(&i));
+        }
+    }
+
+    #[test]
+    fn shrink_to_keeps_requested_minimum() {
+        let mut h = HashMap::<u64, u64>::with_capacity(10_000);
+        h.insert(1, 1);
+
+        h.shrink_to(500);
+        assert!(h.capacity() >= 500);
+        assert!(h.capacity() < 10_000);
+        assert_eq!(h.get(&1), Some(&1));
+
+        let buckets = h.capacity;
+        h.shrink_to(100_000);
+        assert_eq!(h.capacity, buckets);
+    }
+
+    #[test]
+    fn builder_sets_load_factor_and_capacity() {
+        let h = HashMapBuilder::new()
+            .capacity(100)
+            .max_load_factor(2.0)
+            .build::<u64, u64>();
+
+        assert_eq!(h.max_load_factor(), 2.0);
+        assert_eq!(h.capacity, 64);
+        assert_eq!(h.capacity(), 128);
+    }
+
+    #[test]
+    fn custom_load_factor_controls_when_map_grows() {
+        let mut h = HashMapBuilder::new()
+            .max_load_factor(0.5)
+            .build::<u64, u64>();
+        for i in 0..8 {
+            h.insert(i, i);
+        }
+        assert_eq!(h.capacity, 16);
+
+        h.insert(8, 8);
+        assert_eq!(h.capacity, 32);
+    }
+
+    #[test]
+    fn one_and_half_policy_grows_by_half() {
+        let mut h = HashMapBuilder::new()
+            .growth_policy(GrowthPolicy::OneAndHalf)
+            .build::<u64, u64>();
+        for i in 0..13 {
+            h.insert(i, i);
+        }
+        assert_eq!(h.capacity, 24);
+        for i in 0..13 {
+            assert_eq!(h.get(&i), Some(&i));
+        }
+    }
+
+    #[test]
+    fn prime_policy_keeps_bucket_count_prime() {
+        let mut h = HashMapBuilder::new()
+            .growth_policy(GrowthPolicy::Prime)
+            .build::<u64, u64>();
+        assert_eq!(h.capacity, 17);
+
+        for i in 0..1000 {
+            h.insert(i, i);
+            assert!(is_prime(h.capacity));
+        }
+        for i in 0..1000 {
+            assert_eq!(h.get(&i), Some(&i));
+        }
+    }
+
+    #[test]
+    fn builder_accepts_custom_hasher_and_resize_mode() {
+        let h = HashMapBuilder::new()
+            .hasher(RandomState::with_seed(3))
+            .resize_mode(ResizeMode::incremental())
+            .build::<u64, u64>();
+
+        assert_eq!(h.hasher(), &RandomState::with_seed(3));
+        assert_eq!(h.resize_mode(), ResizeMode::incremental());
+    }
+
+    #[test]
+    #[should_panic(expected = "max load factor must be positive and finite")]
+    fn builder_rejects_invalid_load_factor() {
+        HashMapBuilder::new().max_load_factor(0.0);
+    }
+
+    #[test]
+    fn primes_are_detected() {
+        let primes: Vec<usize> = (0..30).filter(|&n| is_prime(n)).collect();
+        assert_eq!(primes, vec![2, 3, 5, 7, 11, 13, 17, 19, 23, 29]);
+        assert_eq!(next_prime(32), 37);
+    }
+}
@@ -0,0 +1,458 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::ops::Deref;
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::{DefaultHashBuilder, HashMap, Location, RandomState};
+
+const CONCURRENT_DEFAULT_SHARDS: usize = 16;
+
+pub struct ConcurrentHashMap<K, V, S = DefaultHashBuilder> {
+    shards: Vec<RwLock<HashMap<K, V, S>>>,
+    shift: u32,
+    hash_builder: S,
+}
+
+pub struct ReadGuard<'a, K, V, S> {
+    shard: RwLockReadGuard<'a, HashMap<K, V, S>>,
+    location: Location,
+}
+
+impl<K: Eq + Hash, V> Default for ConcurrentHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash, V> ConcurrentHashMap<K, V> {
+    pub fn new() -> Self {
+        Self::with_shards(CONCURRENT_DEFAULT_SHARDS)
+    }
+
+    pub fn with_shards(shards: usize) -> Self {
+        Self::with_shards_and_hasher(shards, RandomState::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_shards_and_hasher(CONCURRENT_DEFAULT_SHARDS, RandomState::with_seed(seed))
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Clone> ConcurrentHashMap<K, V, S> {
+    pub fn with_shards_and_hasher(shards: usize, hash_builder: S) -> Self {
+        assert!(shards > 0, "shard count must be positive");
+        let shards = shards.next_power_of_two();
+
+        Self {
+            shards: (0..shards)
+                .map(|_| RwLock::new(HashMap::with_hasher(hash_builder.clone())))
+                .collect(),
+            shift: u64::BITS - shards.trailing_zeros(),
+            hash_builder,
+        }
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    pub fn len(&self) -> usize {
+        (0..self.shards.len()).map(|i| self.read(i).len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..self.shards.len()).all(|i| self.read(i).is_empty())
+    }
+
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        self.write(self.shard_of(&key)).insert(key, value)
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.read(self.shard_of(key)).contains(key)
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<ReadGuard<'_, K, V, S>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let shard = self.read(self.shard_of(key));
+        let location = shard.locate(key)?;
+        Some(ReadGuard { shard, location })
+    }
+
+    pub fn get_cloned<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+        V: Clone,
+    {
+        self.read(self.shard_of(key)).get(key).cloned()
+    }
+
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.write(self.shard_of(key)).remove(key)
+    }
+
+    pub fn update<Q, F, R>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+        F: FnOnce(&mut V) -> R,
+    {
+        self.write(self.shard_of(key)).get_mut(key).map(f)
+    }
+
+    pub fn compute_if_absent<F>(&self, key: K, f: F) -> ReadGuard<'_, K, V, S>
+    where
+        F: FnOnce(&K) -> V,
+    {
+        if let Some(guard) = self.get(&key) {
+            return guard;
+        }
+
+        let mut shard = self.write(self.shard_of(&key));
+        let location = shard.locate_or_insert_with(key, f);
+        let shard = RwLockWriteGuard::downgrade(shard);
+        ReadGuard { shard, location }
+    }
+
+    pub fn clear(&self) {
+        for i in 0..self.shards.len() {
+            self.write(i).clear();
+        }
+    }
+
+    fn shard_of<Q: ?Sized + Hash>(&self, key: &Q) -> usize {
+        if self.shards.len() == 1 {
+            return 0;
+        }
+        (self.hash_builder.hash_one(key) >> self.shift) as usize
+    }
+
+    fn read(&self, index: usize) -> RwLockReadGuard<'_, HashMap<K, V, S>> {
+        self.shards[index]
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self, index: usize) -> RwLockWriteGuard<'_, HashMap<K, V, S>> {
+        self.shards[index]
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> HashMap<K, V, S> {
+    fn locate_or_insert_with<F>(&mut self, key: K, f: F) -> Location
+    where
+        F: FnOnce(&K) -> V,
+    {
+        if self.should_resize() {
+            self.resize();
+        }
+        self.migrate_step();
+
+        let hash = self.hash(&key);
+        self.migrate_key(hash, &key);
+        let bucket = self.bucket_index(hash);
+
+        let pos = match self.buckets[bucket].find(hash, &key) {
+            Some(pos) => pos,
+            None => {
+                let value = f(&key);
+                self.counter += 1;
+                self.buckets[bucket].insert(hash, key, value, self.key_tag)
+            }
+        };
+
+        Location {
+            migrating: false,
+            bucket,
+            pos,
+        }
+    }
+}
+
+impl<K, V, S> ReadGuard<'_, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    pub fn key(&self) -> &K {
+        self.shard.pair_at(self.location).0
+    }
+}
+
+impl<K, V, S> Deref for ReadGuard<'_, K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        self.shard.pair_at(self.location).1
+    }
+}
+
+impl<K, V, S> fmt::Debug for ReadGuard<'_, K, V, S>
+where
+    K: Eq + Hash + fmt::Debug,
+    V: fmt::Debug,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (k, v) = self.shard.pair_at(self.location);
+        f.debug_tuple("ReadGuard").field(k).field(v).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+
+    use super::ConcurrentHashMap;
+
+    const THREADS: usize = 8;
+
+    #[test]
+    fn new_map_is_empty() {
+        let m = ConcurrentHashMap::<String, i32>::new();
+        assert!(m.is_empty());
+        assert_eq!(m.len(), 0);
+        assert_eq!(m.shard_count(), 16);
+    }
+
+    #[test]
+    fn shard_count_rounds_up_to_power_of_two() {
+        assert_eq!(
+            ConcurrentHashMap::<i32, i32>::with_shards(5).shard_count(),
+            8
+        );
+        assert_eq!(
+            ConcurrentHashMap::<i32, i32>::with_shards(1).shard_count(),
+            1
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "shard count must be positive")]
+    fn zero_shards_panics() {
+        ConcurrentHashMap::<i32, i32>::with_shards(0);
+    }
+
+    #[test]
+    fn insert_get_and_remove() {
+        let m = ConcurrentHashMap::new();
+        assert_eq!(m.insert("a".to_string(), 1), None);
+        assert_eq!(m.insert("a".to_string(), 2), Some(1));
+
+        assert_eq!(*m.get("a").unwrap(), 2);
+        assert_eq!(m.get("a").unwrap().key(), "a");
+        assert_eq!(m.get_cloned("a"), Some(2));
+        assert!(m.get("b").is_none());
+        assert!(m.contains("a"));
+
+        assert_eq!(m.remove("a"), Some(2));
+        assert_eq!(m.remove("a"), None);
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn update_applies_closure_to_existing_value() {
+        let m = ConcurrentHashMap::new();
+        m.insert("a", vec![1]);
+
+        assert_eq!(
+            m.update("a", |v| {
+                v.push(2);
+                v.len()
+            }),
+            Some(2)
+        );
+        assert_eq!(m.update("b", |v| v.len()), None);
+        assert_eq!(m.get_cloned("a"), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn compute_if_absent_only_computes_once() {
+        let m = ConcurrentHashMap::new();
+        let mut calls = 0;
+
+        assert_eq!(
+            *m.compute_if_absent("a", |k| {
+                calls += 1;
+                k.len()
+            }),
+            1
+        );
+        assert_eq!(
+            *m.compute_if_absent("a", |_| {
+                calls += 1;
+                99
+            }),
+            1
+        );
+        assert_eq!(calls, 1);
+        assert_eq!(m.len(), 1);
+    }
+
+    #[test]
+    fn keys_spread_across_shards() {
+        let m = ConcurrentHashMap::with_seed(7);
+        for i in 0..4096 {
+            m.insert(i, i);
+        }
+
+        for i in 0..m.shard_count() {
+            let len = m.read(i).len();
+            assert!((128..=384).contains(&len), "shard {i} has {len} keys");
+        }
+    }
+
+    #[test]
+    fn clear_empties_every_shard() {
+        let m = ConcurrentHashMap::new();
+        for i in 0..100 {
+            m.insert(i, i);
+        }
+        m.clear();
+        assert!(m.is_empty());
+    }
+
+    #[test]
+    fn stress_disjoint_inserts_from_many_threads() {
+        let m = ConcurrentHashMap::new();
+        let per_thread = 2_000;
+
+        thread::scope(|s| {
+            for t in 0..THREADS {
+                let m = &m;
+                s.spawn(move || {
+                    for i in 0..per_thread {
+                        let key = t * per_thread + i;
+                        assert_eq!(m.insert(key, key * 2), None);
+                    }
+                });
+            }
+        });
+
+        assert_eq!(m.len(), THREADS * per_thread);
+        for key in 0..THREADS * per_thread {
+            assert_eq!(m.get_cloned(&key), Some(key * 2));
+        }
+    }
+
+    #[test]
+    fn stress_concurrent_updates_are_not_lost() {
+        let m = ConcurrentHashMap::new();
+        let rounds = 5_000;
+        for k in 0..16 {
+            m.insert(k, 0usize);
+        }
+
+        thread::scope(|s| {
+            for t in 0..THREADS {
+                let m = &m;
+                s.spawn(move || {
+                    for i in 0..rounds {
+                        m.update(&((t + i) % 16), |v| *v += 1);
+                    }
+                });
+            }
+        });
+
+        let total: usize = (0..16).map(|k| m.get_cloned(&k).unwrap()).sum();
+        assert_eq!(total, THREADS * rounds);
+    }
+
+    #[test]
+    fn stress_compute_if_absent_runs_once_per_key() {
+        let m = ConcurrentHashMap::new();
+        let computed = AtomicUsize::new(0);
+
+        thread::scope(|s| {
+            for _ in 0..THREADS {
+                let (m, computed) = (&m, &computed);
+                s.spawn(move || {
+                    for k in 0..1_000 {
+                        let v = m.compute_if_absent(k, |k| {
+                            computed.fetch_add(1, Ordering::Relaxed);
+                            k * 10
+                        });
+                        assert_eq!(*v, k * 10);
+                    }
+                });
+            }
+        });
+
+        assert_eq!(computed.load(Ordering::Relaxed), 1_000);
+        assert_eq!(m.len(), 1_000);
+    }
+
+    #[test]
+    fn stress_readers_and_writers_interleave() {
+        let m = Arc::new(ConcurrentHashMap::new());
+        for k in 0..256 {
+            m.insert(k, k);
+        }
+
+        let writers: Vec<_> = (0..THREADS / 2)
+            .map(|t| {
+                let m = Arc::clone(&m);
+                thread::spawn(move || {
+                    for i in 0..5_000 {
+                        let k = (t * 31 + i) % 256;
+                        if i % 3 == 0 {
+                            m.remove(&k);
+                        } else {
+                            m.insert(k, k);
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let readers: Vec<_> = (0..THREADS / 2)
+            .map(|_| {
+                let m = Arc::clone(&m);
+                thread::spawn(move || {
+                    for i in 0..5_000 {
+                        let k = i % 256;
+                        if let Some(v) = m.get(&k) {
+                            assert_eq!(*v, k);
+                            assert_eq!(*v.key(), k);
+                        }
+                        assert!(m.len() <= 256);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in writers.into_iter().chain(readers) {
+            handle.join().unwrap();
+        }
+
+        for k in 0..256 {
+            m.insert(k, k);
+        }
+        assert_eq!(m.len(), 256);
+    }
+}
@@ -0,0 +1,408 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::ops::{Add, BitAnd, BitOr, Sub};
+
+use crate::{DefaultHashBuilder, HashMap, Iter, RandomState};
+
+#[derive(Clone)]
+pub struct Counter<T, S = DefaultHashBuilder> {
+    counts: HashMap<T, usize, S>,
+    total: usize,
+}
+
+type Ranked<'a, T> = (&'a T, usize);
+
+fn outranks<T: Ord>(a: &Ranked<'_, T>, b: &Ranked<'_, T>) -> bool {
+    a.1 > b.1 || (a.1 == b.1 && a.0 < b.0)
+}
+
+fn sift_up<T: Ord>(heap: &mut [Ranked<'_, T>], mut i: usize) {
+    while i > 0 {
+        let parent = (i - 1) / 2;
+        if !outranks(&heap[parent], &heap[i]) {
+            break;
+        }
+        heap.swap(i, parent);
+        i = parent;
+    }
+}
+
+fn sift_down<T: Ord>(heap: &mut [Ranked<'_, T>], mut i: usize) {
+    loop {
+        let mut worst = i;
+        for child in [2 * i + 1, 2 * i + 2] {
+            if child < heap.len() && outranks(&heap[worst], &heap[child]) {
+                worst = child;
+            }
+        }
+        if worst == i {
+            break;
+        }
+        heap.swap(i, worst);
+        i = worst;
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher + Default> Default for Counter<T, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<T: Eq + Hash> Counter<T> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_hasher(RandomState::with_seed(seed))
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher> Counter<T, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            counts: HashMap::with_hasher(hash_builder),
+            total: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    pub fn add(&mut self, item: T) -> usize {
+        self.add_n(item, 1)
+    }
+
+    pub fn add_n(&mut self, item: T, n: usize) -> usize {
+        if n == 0 {
+            return self.count(&item);
+        }
+
+        let count = self.counts.entry(item).or_insert(0);
+        *count += n;
+        self.total += n;
+        *count
+    }
+
+    pub fn subtract<Q>(&mut self, item: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.subtract_n(item, 1)
+    }
+
+    pub fn subtract_n<Q>(&mut self, item: &Q, n: usize) -> usize
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let Some(count) = self.counts.get_mut(item) else {
+            return 0;
+        };
+
+        let removed = n.min(*count);
+        *count -= removed;
+        let left = *count;
+        self.total -= removed;
+
+        if left == 0 {
+            self.counts.remove(item);
+        }
+        left
+    }
+
+    pub fn count<Q>(&self, item: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    pub fn remove<Q>(&mut self, item: &Q) -> usize
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let count = self.counts.remove(item).unwrap_or(0);
+        self.total -= count;
+        count
+    }
+
+    pub fn most_common(&self, k: usize) -> Vec<(&T, usize)>
+    where
+        T: Ord,
+    {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let mut heap: Vec<Ranked<'_, T>> = Vec::with_capacity(k.min(self.len()));
+        for (item, &count) in self.counts.iter() {
+            let ranked = (item, count);
+            if heap.len() < k {
+                heap.push(ranked);
+                let last = heap.len() - 1;
+                sift_up(&mut heap, last);
+            } else if outranks(&ranked, &heap[0]) {
+                heap[0] = ranked;
+                sift_down(&mut heap, 0);
+            }
+        }
+
+        heap.sort_unstable_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        heap
+    }
+
+    pub fn clear(&mut self) {
+        self.counts.clear();
+        self.total = 0;
+    }
+}
+
+impl<T, S> Counter<T, S> {
+    pub fn iter(&self) -> Iter<'_, T, usize> {
+        self.counts.iter()
+    }
+}
+
+impl<T: fmt::Debug, S> fmt::Debug for Counter<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.counts.iter()).finish()
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher> PartialEq for Counter<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.counts == other.counts
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher> Eq for Counter<T, S> {}
+
+impl<T: Eq + Hash, S: BuildHasher + Default> FromIterator<T> for Counter<T, S> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Self::with_hasher(S::default());
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher> Extend<T> for Counter<T, S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for item in iter {
+            self.add(item);
+        }
+    }
+}
+
+impl<T, S> Add<&Counter<T, S>> for &Counter<T, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher + Default,
+{
+    type Output = Counter<T, S>;
+
+    fn add(self, rhs: &Counter<T, S>) -> Counter<T, S> {
+        let mut out = Counter::default();
+        for (item, &n) in self.iter().chain(rhs.iter()) {
+            out.add_n(item.clone(), n);
+        }
+        out
+    }
+}
+
+impl<T, S> Sub<&Counter<T, S>> for &Counter<T, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher + Default,
+{
+    type Output = Counter<T, S>;
+
+    fn sub(self, rhs: &Counter<T, S>) -> Counter<T, S> {
+        let mut out = Counter::default();
+        for (item, &n) in self.iter() {
+            out.add_n(item.clone(), n.saturating_sub(rhs.count(item)));
+        }
+        out
+    }
+}
+
+impl<T, S> BitAnd<&Counter<T, S>> for &Counter<T, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher + Default,
+{
+    type Output = Counter<T, S>;
+
+    fn bitand(self, rhs: &Counter<T, S>) -> Counter<T, S> {
+        let mut out = Counter::default();
+        for (item, &n) in self.iter() {
+            out.add_n(item.clone(), n.min(rhs.count(item)));
+        }
+        out
+    }
+}
+
+impl<T, S> BitOr<&Counter<T, S>> for &Counter<T, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher + Default,
+{
+    type Output = Counter<T, S>;
+
+    fn bitor(self, rhs: &Counter<T, S>) -> Counter<T, S> {
+        let mut out = Counter::default();
+        for (item, &n) in self.iter() {
+            out.add_n(item.clone(), n.max(rhs.count(item)));
+        }
+        for (item, &n) in rhs.iter() {
+            if !self.counts.contains(item) {
+                out.add_n(item.clone(), n);
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Counter;
+
+    fn words(text: &str) -> Counter<&str> {
+        text.split_whitespace().collect()
+    }
+
+    #[test]
+    fn new_counter_is_empty() {
+        let c = Counter::<&str>::new();
+        assert!(c.is_empty());
+        assert_eq!(c.total(), 0);
+        assert_eq!(c.count("a"), 0);
+    }
+
+    #[test]
+    fn add_and_add_n_accumulate() {
+        let mut c = Counter::new();
+        assert_eq!(c.add("a"), 1);
+        assert_eq!(c.add("a"), 2);
+        assert_eq!(c.add_n("b", 5), 5);
+        assert_eq!(c.add_n("c", 0), 0);
+
+        assert_eq!(c.count("a"), 2);
+        assert_eq!(c.len(), 2);
+        assert_eq!(c.total(), 7);
+    }
+
+    #[test]
+    fn subtract_saturates_and_drops_zero_counts() {
+        let mut c = words("a a b");
+        assert_eq!(c.subtract("a"), 1);
+        assert_eq!(c.subtract_n("a", 10), 0);
+        assert_eq!(c.subtract("missing"), 0);
+
+        assert_eq!(c.len(), 1);
+        assert_eq!(c.total(), 1);
+        assert!(c.iter().all(|(_, &n)| n > 0));
+    }
+
+    #[test]
+    fn remove_returns_full_count() {
+        let mut c = words("a a b");
+        assert_eq!(c.remove("a"), 2);
+        assert_eq!(c.remove("a"), 0);
+        assert_eq!(c.total(), 1);
+    }
+
+    #[test]
+    fn most_common_returns_top_k_in_descending_order() {
+        let c = words("e d e c d e b c d e a b c d e");
+        assert_eq!(c.most_common(3), vec![(&"e", 5), (&"d", 4), (&"c", 3)]);
+        assert_eq!(c.most_common(0), vec![]);
+        assert_eq!(c.most_common(100).len(), 5);
+    }
+
+    #[test]
+    fn most_common_breaks_ties_by_item_order() {
+        let c = words("pear fig kiwi apple fig kiwi apple pear date");
+        assert_eq!(
+            c.most_common(3),
+            vec![(&"apple", 2), (&"fig", 2), (&"kiwi", 2)]
+        );
+        assert_eq!(c.most_common(5)[4], (&"date", 1));
+
+        for seed in 0..16 {
+            let mut seeded = Counter::with_seed(seed);
+            seeded.extend((0..200u32).map(|i| i % 50));
+            let top: Vec<u32> = seeded.most_common(5).into_iter().map(|(k, _)| *k).collect();
+            assert_eq!(top, [0, 1, 2, 3, 4]);
+        }
+    }
+
+    #[test]
+    fn most_common_on_large_input() {
+        let mut c = Counter::new();
+        for i in 0..1000u32 {
+            c.add_n(i, i as usize);
+        }
+
+        let top: Vec<u32> = c.most_common(4).into_iter().map(|(k, _)| *k).collect();
+        assert_eq!(top, [999, 998, 997, 996]);
+    }
+
+    #[test]
+    fn addition_sums_counts() {
+        let sum = &words("a a b") + &words("a c");
+        assert_eq!(sum, words("a a a b c"));
+        assert_eq!(sum.total(), 5);
+    }
+
+    #[test]
+    fn subtraction_keeps_only_positive_counts() {
+        let diff = &words("a a a b c") - &words("a b b d");
+        assert_eq!(diff, words("a a c"));
+        assert_eq!(diff.count("b"), 0);
+        assert_eq!(diff.len(), 2);
+    }
+
+    #[test]
+    fn intersection_takes_minimum() {
+        let inter = &words("a a a b c") & &words("a b b d");
+        assert_eq!(inter, words("a b"));
+    }
+
+    #[test]
+    fn union_takes_maximum() {
+        let union = &words("a a a b c") | &words("a b b d");
+        assert_eq!(union, words("a a a b b c d"));
+        assert_eq!(union.total(), 7);
+    }
+
+    #[test]
+    fn extend_counts_more_items() {
+        let mut c = words("x");
+        c.extend(["x", "y"]);
+        assert_eq!(c.count("x"), 2);
+        assert_eq!(c.total(), 3);
+    }
+
+    #[test]
+    fn clear_resets_total() {
+        let mut c = words("a b c");
+        c.clear();
+        assert!(c.is_empty());
+        assert_eq!(c.total(), 0);
+    }
+}
@@ -0,0 +1,457 @@
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+
+use crate::random_state::splitmix64;
+use crate::{DefaultHashBuilder, RandomState};
+
+const CUCKOO_INITIAL_CAPACITY: usize = 16;
+const CUCKOO_MAX_EVICTIONS: usize = 32;
+const CUCKOO_MAX_REHASH_ATTEMPTS: usize = 8;
+const CUCKOO_STASH_CAPACITY: usize = 4;
+const CUCKOO_MAX_GROWTH: usize = 8;
+
+pub struct CuckooMap<K, V, S = DefaultHashBuilder> {
+    tables: [Vec<Option<(K, V)>>; 2],
+    stash: Vec<(K, V)>,
+    hash_builder: S,
+    seeds: [u64; 2],
+    seed_state: u64,
+    counter: usize,
+    rehashes: usize,
+}
+
+#[derive(Clone, Copy)]
+enum Place {
+    Table(usize, usize),
+    Stash(usize),
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> Default for CuckooMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K: Eq + Hash, V> CuckooMap<K, V> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        let mut map = Self::with_hasher(RandomState::with_seed(seed));
+        map.seed_state = seed;
+        map.reseed();
+        map
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> CuckooMap<K, V, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        let mut slots = CUCKOO_INITIAL_CAPACITY;
+        while capacity > slots {
+            slots *= 2;
+        }
+
+        let mut map = Self {
+            tables: [empty_table(slots), empty_table(slots)],
+            stash: Vec::new(),
+            hash_builder,
+            seeds: [0; 2],
+            seed_state: RandomState::new().keys().0,
+            counter: 0,
+            rehashes: 0,
+        };
+        map.reseed();
+        map
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    pub fn len(&self) -> usize {
+        self.counter
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counter == 0
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get(key).is_some()
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        match self.find(key)? {
+            Place::Table(table, index) => self.tables[table][index].as_ref().map(|(_, v)| v),
+            Place::Stash(index) => Some(&self.stash[index].1),
+        }
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.find(&key) {
+            Some(Place::Table(table, index)) => {
+                return self.tables[table][index]
+                    .as_mut()
+                    .map(|(_, v)| std::mem::replace(v, value));
+            }
+            Some(Place::Stash(index)) => {
+                return Some(std::mem::replace(&mut self.stash[index].1, value));
+            }
+            None => {}
+        }
+
+        if self.counter + 1 > self.capacity() {
+            self.rehash(self.capacity() * 2, Vec::new());
+        }
+
+        if let Err(homeless) = self.try_place((key, value)) {
+            self.rehash(self.capacity(), vec![homeless]);
+        }
+        self.counter += 1;
+        None
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (_, v) = match self.find(key)? {
+            Place::Table(table, index) => self.tables[table][index].take()?,
+            Place::Stash(index) => self.stash.swap_remove(index),
+        };
+        self.counter -= 1;
+        Some(v)
+    }
+
+    pub fn clear(&mut self) {
+        for table in self.tables.iter_mut() {
+            table.iter_mut().for_each(|slot| *slot = None);
+        }
+        self.stash.clear();
+        self.counter = 0;
+    }
+
+    fn capacity(&self) -> usize {
+        self.tables[0].len()
+    }
+
+    fn slot<Q: ?Sized + Hash>(&self, table: usize, key: &Q) -> usize {
+        self.slot_for(table, self.hash_builder.hash_one(key))
+    }
+
+    fn slot_for(&self, table: usize, hash: u64) -> usize {
+        (splitmix64(hash ^ self.seeds[table]) as usize) & (self.capacity() - 1)
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<Place>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let hash = self.hash_builder.hash_one(key);
+        for table in 0..2 {
+            let index = self.slot_for(table, hash);
+            if let Some((k, _)) = &self.tables[table][index]
+                && k.borrow() == key
+            {
+                return Some(Place::Table(table, index));
+            }
+        }
+
+        self.stash
+            .iter()
+            .position(|(k, _)| k.borrow() == key)
+            .map(Place::Stash)
+    }
+
+    fn try_place(&mut self, pair: (K, V)) -> Result<(), (K, V)> {
+        let mut pair = pair;
+        let mut table = 0;
+
+        for _ in 0..CUCKOO_MAX_EVICTIONS {
+            let index = self.slot(table, &pair.0);
+            match self.tables[table][index].replace(pair) {
+                None => return Ok(()),
+                Some(evicted) => {
+                    pair = evicted;
+                    table ^= 1;
+                }
+            }
+        }
+
+        Err(pair)
+    }
+
+    fn rehash(&mut self, mut capacity: usize, mut pending: Vec<(K, V)>) {
+        pending.append(&mut self.stash);
+        let limit = capacity * CUCKOO_MAX_GROWTH;
+
+        loop {
+            for _ in 0..CUCKOO_MAX_REHASH_ATTEMPTS {
+                self.rehashes += 1;
+                self.reseed();
+
+                for table in self.tables.iter_mut() {
+                    pending.extend(table.drain(..).flatten());
+                }
+                self.tables = [empty_table(capacity), empty_table(capacity)];
+
+                let mut homeless = Vec::new();
+                for pair in pending.drain(..) {
+                    if let Err(pair) = self.try_place(pair) {
+                        homeless.push(pair);
+                    }
+                }
+
+                if homeless.len() <= CUCKOO_STASH_CAPACITY {
+                    self.stash = homeless;
+                    return;
+                }
+                pending = homeless;
+            }
+
+            capacity *= 2;
+            assert!(
+                capacity <= limit,
+                "too many keys share a hash: they no longer fit in their two slots and the stash"
+            );
+        }
+    }
+
+    fn reseed(&mut self) {
+        for table in 0..2 {
+            self.seeds[table] = self.next_seed();
+        }
+    }
+
+    fn next_seed(&mut self) -> u64 {
+        self.seed_state = splitmix64(self.seed_state);
+        self.seed_state
+    }
+}
+
+fn empty_table<K, V>(capacity: usize) -> Vec<Option<(K, V)>> {
+    (0..capacity).map(|_| None).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::Hasher;
+
+    use super::*;
+    use crate::HashMap;
+    use crate::fixtures::{ConstantState, IdentityState, Lcg};
+
+    map_test_suite!(CuckooMap);
+
+    fn stored<S>(m: &CuckooMap<u64, u64, S>) -> usize {
+        m.tables.iter().flatten().flatten().count() + m.stash.len()
+    }
+
+    fn keys_sharing_both_slots(m: &CuckooMap<u64, u64>, count: usize) -> Vec<u64> {
+        let target = (m.slot(0, &0u64), m.slot(1, &0u64));
+        (0..)
+            .filter(|k| (m.slot(0, k), m.slot(1, k)) == target)
+            .take(count)
+            .collect()
+    }
+
+    #[test]
+    fn hash_functions_use_independent_seeds() {
+        let m = CuckooMap::<u64, u64>::with_seed(5);
+        assert_ne!(m.seeds[0], m.seeds[1]);
+    }
+
+    #[test]
+    fn every_key_lives_in_one_of_its_two_slots() {
+        let mut m = CuckooMap::<u64, u64>::with_seed(11);
+        for k in 0..1000 {
+            m.insert(k, k);
+        }
+
+        for k in 0..1000u64 {
+            let in_first = m.tables[0][m.slot(0, &k)]
+                .as_ref()
+                .is_some_and(|(key, _)| *key == k);
+            let in_second = m.tables[1][m.slot(1, &k)]
+                .as_ref()
+                .is_some_and(|(key, _)| *key == k);
+            assert!(in_first ^ in_second);
+        }
+        assert_eq!(stored(&m), 1000);
+    }
+
+    #[test]
+    fn load_stays_at_or_below_half_of_all_slots() {
+        let mut m = CuckooMap::<u64, u64>::with_seed(2);
+        for k in 0..5000 {
+            m.insert(k, k);
+            assert!(m.len() <= m.capacity());
+        }
+    }
+
+    #[test]
+    fn with_seed_is_reproducible() {
+        let a = CuckooMap::<u64, u64>::with_seed(77);
+        let b = CuckooMap::<u64, u64>::with_seed(77);
+        assert_eq!(a.seeds, b.seeds);
+        assert_eq!(a.hasher(), b.hasher());
+    }
+
+    #[test]
+    fn eviction_cycle_triggers_rehash_with_fresh_seeds() {
+        let mut m = CuckooMap::<u64, u64>::with_seed(3);
+        let cycle = keys_sharing_both_slots(&m, 3);
+        let seeds_before = m.seeds;
+
+        for &k in &cycle {
+            m.insert(k, k * 10);
+        }
+
+        assert!(m.rehashes >= 1);
+        assert_ne!(m.seeds, seeds_before);
+        assert_eq!(m.len(), 3);
+        assert_eq!(stored(&m), 3);
+        for &k in &cycle {
+            assert_eq!(m.get(&k), Some(&(k * 10)));
+        }
+    }
+
+    #[test]
+    fn repeated_forced_cycles_never_lose_entries() {
+        let mut m = CuckooMap::<u64, u64>::with_seed(4);
+        let mut inserted = Vec::new();
+
+        for _ in 0..20 {
+            let cycle: Vec<u64> = keys_sharing_both_slots(&m, 3)
+                .into_iter()
+                .filter(|k| !inserted.contains(k))
+                .collect();
+            for k in cycle {
+                m.insert(k, k);
+                inserted.push(k);
+            }
+        }
+
+        assert!(m.rehashes >= 5);
+        assert_eq!(m.len(), inserted.len());
+        assert_eq!(stored(&m), inserted.len());
+        for k in &inserted {
+            assert_eq!(m.get(k), Some(k));
+        }
+    }
+
+    #[test]
+    fn stress_matches_chained_hash_map() {
+        let mut cuckoo = CuckooMap::<u64, u64>::with_seed(8);
+        let mut reference = HashMap::<u64, u64>::with_seed(8);
+        let mut rng = Lcg::new(7);
+
+        for _ in 0..50_000 {
+            let key = rng.below(4000);
+            if rng.below(4) == 0 {
+                assert_eq!(cuckoo.remove(&key), reference.remove(&key));
+            } else {
+                let value = rng.next_u64();
+                cuckoo.insert(key, value);
+                reference.insert(key, value);
+            }
+        }
+
+        assert_eq!(cuckoo.len(), reference.len());
+        assert_eq!(stored(&cuckoo), reference.len());
+        for key in 0..4000 {
+            assert_eq!(cuckoo.get(&key), reference.get(&key));
+        }
+    }
+
+    #[test]
+    fn with_hasher_uses_given_hasher() {
+        let mut m = CuckooMap::with_hasher(IdentityState::default());
+        for k in 0..1000u64 {
+            m.insert(k, k * 2);
+        }
+
+        assert_eq!(m.len(), 1000);
+        assert_eq!(stored(&m), 1000);
+        for k in 0..1000u64 {
+            assert_eq!(m.get(&k), Some(&(k * 2)));
+        }
+    }
+
+    #[test]
+    fn with_capacity_and_hasher_avoids_growth_for_requested_capacity() {
+        let mut m = CuckooMap::with_capacity_and_hasher(1000, RandomState::new());
+        let capacity = m.capacity();
+        assert!(capacity >= 1000);
+
+        for k in 0..1000u64 {
+            m.insert(k, k);
+        }
+        assert_eq!(m.capacity(), capacity);
+    }
+
+    #[derive(PartialEq, Eq)]
+    struct Twin(u64);
+
+    impl Hash for Twin {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            state.write_u64(self.0 / 2);
+        }
+    }
+
+    #[test]
+    fn shared_hashes_keep_the_stash_bounded() {
+        let mut m = CuckooMap::with_hasher(IdentityState::default());
+        for k in 0..2000u64 {
+            m.insert(Twin(k), k);
+            assert!(m.stash.len() <= CUCKOO_STASH_CAPACITY);
+        }
+
+        assert_eq!(m.len(), 2000);
+        for k in 0..2000u64 {
+            assert_eq!(m.get(&Twin(k)), Some(&k));
+        }
+
+        assert_eq!(m.remove(&Twin(7)), Some(7));
+        assert!(!m.contains(&Twin(7)));
+        assert_eq!(m.get(&Twin(6)), Some(&6));
+    }
+
+    #[test]
+    fn constant_hasher_stashes_only_a_handful_of_keys() {
+        let mut m = CuckooMap::with_hasher(ConstantState::default());
+        for k in 0..6u64 {
+            m.insert(k, k);
+        }
+
+        assert_eq!(m.len(), 6);
+        assert!(m.stash.len() <= CUCKOO_STASH_CAPACITY);
+        for k in 0..6u64 {
+            assert_eq!(m.get(&k), Some(&k));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "too many keys share a hash")]
+    fn constant_hasher_panics_instead_of_scanning_a_long_stash() {
+        let mut m = CuckooMap::with_hasher(ConstantState::default());
+        for k in 0..7u64 {
+            m.insert(k, k);
+        }
+    }
+}
@@ -0,0 +1,363 @@
+use std::fmt;
+
+use crate::bucket::{Bucket, KeyTag};
+
+pub enum Entry<'a, K, V> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V>),
+}
+
+pub struct OccupiedEntry<'a, K, V> {
+    bucket: &'a mut Bucket<K, V>,
+    counter: &'a mut usize,
+    pos: usize,
+}
+
+pub struct VacantEntry<'a, K, V> {
+    bucket: &'a mut Bucket<K, V>,
+    counter: &'a mut usize,
+    hash: u64,
+    key: K,
+    key_tag: KeyTag<K>,
+}
+
+pub struct OccupiedError<'a, K, V> {
+    pub entry: OccupiedEntry<'a, K, V>,
+    pub value: V,
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Debug for OccupiedError<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OccupiedError")
+            .field("key", self.entry.key())
+            .field("old_value", self.entry.get())
+            .field("new_value", &self.value)
+            .finish()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> fmt::Display for OccupiedError<'_, K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "failed to insert {:?}, key {:?} already exists with value {:?}",
+            self.value,
+            self.entry.key(),
+            self.entry.get(),
+        )
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug> std::error::Error for OccupiedError<'_, K, V> {}
+
+impl<'a, K, V> Entry<'a, K, V> {
+    pub(crate) fn occupied(
+        bucket: &'a mut Bucket<K, V>,
+        counter: &'a mut usize,
+        pos: usize,
+    ) -> Self {
+        Entry::Occupied(OccupiedEntry {
+            bucket,
+            counter,
+            pos,
+        })
+    }
+
+    pub(crate) fn vacant(
+        bucket: &'a mut Bucket<K, V>,
+        counter: &'a mut usize,
+        hash: u64,
+        key: K,
+        key_tag: KeyTag<K>,
+    ) -> Self {
+        Entry::Vacant(VacantEntry {
+            bucket,
+            counter,
+            hash,
+            key,
+            key_tag,
+        })
+    }
+
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(e) => e.key(),
+            Entry::Vacant(e) => e.key(),
+        }
+    }
+
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default),
+        }
+    }
+
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => e.insert(default()),
+        }
+    }
+
+    pub fn or_insert_with_key<F: FnOnce(&K) -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(e) => e.into_mut(),
+            Entry::Vacant(e) => {
+                let value = default(&e.key);
+                e.insert(value)
+            }
+        }
+    }
+
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(e) = &mut self {
+            f(e.get_mut());
+        }
+        self
+    }
+}
+
+impl<'a, K, V: Default> Entry<'a, K, V> {
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.bucket.entries()[self.pos].0
+    }
+
+    pub fn get(&self) -> &V {
+        &self.bucket.entries()[self.pos].1
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.bucket.entries_mut()[self.pos].1
+    }
+
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.bucket.entries_mut()[self.pos].1
+    }
+
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    pub fn remove(self) -> V {
+        self.remove_entry().1
+    }
+
+    pub fn remove_entry(self) -> (K, V) {
+        let pair = self.bucket.remove(self.pos);
+        *self.counter -= 1;
+        pair
+    }
+}
+
+impl<'a, K, V> VacantEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    pub fn insert(self, value: V) -> &'a mut V {
+        let pos = self.bucket.insert(self.hash, self.key, value, self.key_tag);
+        *self.counter += 1;
+
+        &mut self.bucket.entries_mut()[pos].1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HashMap;
+
+    use super::Entry;
+
+    #[test]
+    fn entry_on_missing_key_is_vacant() {
+        let mut h = HashMap::<String, i32>::new();
+        assert!(matches!(h.entry("a".to_string()), Entry::Vacant(_)));
+    }
+
+    #[test]
+    fn entry_on_existing_key_is_occupied() {
+        let mut h = HashMap::<String, i32>::new();
+        h.insert("a".to_string(), 1);
+        assert!(matches!(h.entry("a".to_string()), Entry::Occupied(_)));
+    }
+
+    #[test]
+    fn entry_key_returns_key_for_both_variants() {
+        let mut h = HashMap::<String, i32>::new();
+        h.insert("a".to_string(), 1);
+        assert_eq!(h.entry("a".to_string()).key(), "a");
+        assert_eq!(h.entry("b".to_string()).key(), "b");
+    }
+
+    #[test]
+    fn or_insert_inserts_when_vacant() {
+        let mut h = HashMap::<String, i32>::new();
+        assert_eq!(*h.entry("a".to_string()).or_insert(1), 1);
+        assert_eq!(h.len(), 1);
+        assert_eq!(h.get("a"), Some(&1));
+    }
+
+    #[test]
+    fn or_insert_keeps_existing_value_when_occupied() {
+        let mut h = HashMap::<String, i32>::new();
+        h.insert("a".to_string(), 1);
+        assert_eq!(*h.entry("a".to_string()).or_insert(2), 1);
+        assert_eq!(h.len(), 1);
+    }
+
+    #[test]
+    fn or_insert_returns_mutable_reference_into_map() {
+        let mut h = HashMap::<String, i32>::new();
+        *h.entry("a".to_string()).or_insert(0) += 5;
+        *h.entry("a".to_string()).or_insert(0) += 5;
+        assert_eq!(h.get("a"), Some(&10));
+    }
+
+    #[test]
+    fn or_insert_with_only_calls_closure_when_vacant() {
+        let mut h = HashMap::<String, i32>::new();
+        let mut calls = 0;
+
+        h.entry("a".to_string()).or_insert_with(|| {
+            calls += 1;
+            1
+        });
+        h.entry("a".to_string()).or_insert_with(|| {
+            calls += 1;
+            2
+        });
+
+        assert_eq!(calls, 1);
+        assert_eq!(h.get("a"), Some(&1));
+    }
+
+    #[test]
+    fn or_insert_with_key_receives_the_key() {
+        let mut h = HashMap::<String, usize>::new();
+        h.entry("abc".to_string()).or_insert_with_key(|k| k.len());
+        assert_eq!(h.get("abc"), Some(&3));
+    }
+
+    #[test]
+    fn or_default_inserts_default_value() {
+        let mut h = HashMap::<String, Vec<i32>>::new();
+        h.entry("a".to_string()).or_default().push(1);
+        h.entry("a".to_string()).or_default().push(2);
+        assert_eq!(h.get("a"), Some(&vec![1, 2]));
+        assert_eq!(h.len(), 1);
+    }
+
+    #[test]
+    fn and_modify_only_runs_when_occupied() {
+        let mut h = HashMap::<String, i32>::new();
+
+        h.entry("a".to_string())
+            .and_modify(|v| *v += 1)
+            .or_insert(1);
+        assert_eq!(h.get("a"), Some(&1));
+
+        h.entry("a".to_string())
+            .and_modify(|v| *v += 1)
+            .or_insert(1);
+        assert_eq!(h.get("a"), Some(&2));
+    }
+
+    #[test]
+    fn occupied_insert_replaces_and_returns_old_value() {
+        let mut h = HashMap::<String, i32>::new();
+        h.insert("a".to_string(), 1);
+
+        if let Entry::Occupied(mut e) = h.entry("a".to_string()) {
+            assert_eq!(e.insert(2), 1);
+            assert_eq!(e.get(), &2);
+        } else {
+            panic!("expected occupied entry");
+        }
+
+        assert_eq!(h.get("a"), Some(&2));
+        assert_eq!(h.len(), 1);
+    }
+
+    #[test]
+    fn occupied_remove_entry_removes_pair_and_decreases_len() {
+        let mut h = HashMap::<String, i32>::new();
+        h.insert("a".to_string(), 1);
+        h.insert("b".to_string(), 2);
+
+        if let Entry::Occupied(e) = h.entry("a".to_string()) {
+            assert_eq!(e.remove_entry(), ("a".to_string(), 1));
+        } else {
+            panic!("expected occupied entry");
+        }
+
+        assert_eq!(h.len(), 1);
+        assert!(!h.contains("a"));
+        assert_eq!(h.get("b"), Some(&2));
+    }
+
+    #[test]
+    fn occupied_remove_returns_value() {
+        let mut h = HashMap::<String, i32>::new();
+        h.insert("a".to_string(), 1);
+
+        if let Entry::Occupied(e) = h.entry("a".to_string()) {
+            assert_eq!(e.remove(), 1);
+        } else {
+            panic!("expected occupied entry");
+        }
+
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn vacant_insert_increases_len() {
+        let mut h = HashMap::<String, i32>::new();
+
+        if let Entry::Vacant(e) = h.entry("a".to_string()) {
+            assert_eq!(e.key(), "a");
+            *e.insert(1) += 1;
+        } else {
+            panic!("expected vacant entry");
+        }
+
+        assert_eq!(h.len(), 1);
+        assert_eq!(h.get("a"), Some(&2));
+    }
+
+    #[test]
+    fn entry_counts_words() {
+        let mut h = HashMap::<&str, usize>::new();
+        for word in "a b a c b a".split(' ') {
+            *h.entry(word).or_insert(0) += 1;
+        }
+
+        assert_eq!(h.len(), 3);
+        assert_eq!(h.get("a"), Some(&3));
+        assert_eq!(h.get("b"), Some(&2));
+        assert_eq!(h.get("c"), Some(&1));
+    }
+
+    #[test]
+    fn entry_inserts_survive_resize() {
+        let mut h = HashMap::<String, usize>::new();
+        for i in 0..500 {
+            *h.entry(format!("k{i}")).or_insert(0) += i;
+        }
+
+        assert_eq!(h.len(), 500);
+        for i in 0..500 {
+            assert_eq!(h.get(format!("k{i}").as_str()), Some(&i));
+        }
+    }
+}
@@ -0,0 +1,9 @@
+mod fnv;
+mod fx;
+mod quality;
+mod sip;
+
+pub use fnv::{FnvBuildHasher, FnvHasher};
+pub use fx::{FxBuildHasher, FxHasher};
+pub use quality::DistributionReport;
+pub use sip::{SipBuildHasher, SipHasher, SipHasher13, SipHasher24};
@@ -0,0 +1,82 @@
+use std::hash::{BuildHasherDefault, Hasher};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+pub type FnvBuildHasher = BuildHasherDefault<FnvHasher>;
+
+pub struct FnvHasher {
+    state: u64,
+}
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FnvHasher {
+    pub fn new() -> Self {
+        Self {
+            state: FNV_OFFSET_BASIS,
+        }
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= *byte as u64;
+            self.state = self.state.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{BuildHasher, Hasher};
+
+    use super::*;
+    use crate::HashMap;
+
+    fn fnv1a(bytes: &[u8]) -> u64 {
+        let mut h = FnvHasher::new();
+        h.write(bytes);
+        h.finish()
+    }
+
+    #[test]
+    fn matches_reference_vectors() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn split_writes_match_single_write() {
+        let mut h = FnvHasher::new();
+        h.write(b"foo");
+        h.write(b"bar");
+        assert_eq!(h.finish(), fnv1a(b"foobar"));
+    }
+
+    #[test]
+    fn build_hasher_creates_fresh_hashers() {
+        let b = FnvBuildHasher::default();
+        assert_eq!(b.hash_one(42u32), b.hash_one(42u32));
+    }
+
+    #[test]
+    fn plugs_into_hash_map() {
+        let mut m = HashMap::with_hasher(FnvBuildHasher::default());
+        for i in 0..100 {
+            m.insert(i.to_string(), i);
+        }
+        assert_eq!(m.len(), 100);
+        assert_eq!(m.get("42"), Some(&42));
+    }
+}
@@ -0,0 +1,176 @@
+use std::hash::{BuildHasherDefault, Hasher};
+
+const FX_SEED: u64 = 0xf135_7aea_2e62_a9c5;
+const FX_FINISH_ROTATE: u32 = 26;
+const FX_BYTES_SEED0: u64 = 0x243f_6a88_85a3_08d3;
+const FX_BYTES_SEED1: u64 = 0x1319_8a2e_0370_7344;
+const FX_BYTES_ZERO_GUARD: u64 = 0xa409_3822_299f_31d0;
+
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
+
+#[derive(Default)]
+pub struct FxHasher {
+    state: u64,
+}
+
+impl FxHasher {
+    pub fn new() -> Self {
+        Self { state: 0 }
+    }
+
+    fn add_to_hash(&mut self, word: u64) {
+        self.state = self.state.wrapping_add(word).wrapping_mul(FX_SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.add_to_hash(hash_bytes(bytes));
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.add_to_hash(i as u64);
+        self.add_to_hash((i >> 64) as u64);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.state.rotate_left(FX_FINISH_ROTATE)
+    }
+}
+
+fn multiply_mix(x: u64, y: u64) -> u64 {
+    let full = (x as u128) * (y as u128);
+    (full as u64) ^ ((full >> 64) as u64)
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes[..8].try_into().expect("slice has 8 bytes"))
+}
+
+fn read_u32(bytes: &[u8]) -> u64 {
+    u32::from_le_bytes(bytes[..4].try_into().expect("slice has 4 bytes")) as u64
+}
+
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let len = bytes.len();
+    let mut s0 = FX_BYTES_SEED0;
+    let mut s1 = FX_BYTES_SEED1;
+
+    if len > 16 {
+        let mut off = 0;
+        while off < len - 16 {
+            let x = read_u64(&bytes[off..]);
+            let y = read_u64(&bytes[off + 8..]);
+            let t = multiply_mix(s0 ^ x, FX_BYTES_ZERO_GUARD ^ y);
+            s0 = s1;
+            s1 = t;
+            off += 16;
+        }
+
+        let suffix = &bytes[len - 16..];
+        s0 ^= read_u64(suffix);
+        s1 ^= read_u64(&suffix[8..]);
+    } else if len >= 8 {
+        s0 ^= read_u64(bytes);
+        s1 ^= read_u64(&bytes[len - 8..]);
+    } else if len >= 4 {
+        s0 ^= read_u32(bytes);
+        s1 ^= read_u32(&bytes[len - 4..]);
+    } else if len > 0 {
+        s0 ^= bytes[0] as u64;
+        s1 ^= ((bytes[len - 1] as u64) << 8) | bytes[len / 2] as u64;
+    }
+
+    multiply_mix(s0, s1) ^ len as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{BuildHasher, Hash, Hasher};
+
+    use super::*;
+    use crate::HashMap;
+
+    #[test]
+    fn empty_input_hashes_to_zero() {
+        assert_eq!(FxHasher::new().finish(), 0);
+    }
+
+    #[test]
+    fn matches_rustc_hash_integer_vectors() {
+        let b = FxBuildHasher::default();
+        assert_eq!(b.hash_one(0u64), 0);
+        assert_eq!(b.hash_one(1u8), 12157901119326311915);
+        assert_eq!(b.hash_one(100u16), 16751747135202103309);
+        assert_eq!(b.hash_one(u32::MAX), 7729994835221066939);
+        assert_eq!(b.hash_one(u64::MAX), 6288842954450348564);
+        assert_eq!(b.hash_one(1u128), 13032756267696824044);
+        assert_eq!(b.hash_one(u128::MAX), 11702830760530184999);
+        assert_eq!(b.hash_one(i8::MIN), 6684841074112525780);
+        assert_eq!(b.hash_one(i64::MIN), 33554432);
+        assert_eq!(b.hash_one(i128::MAX), 11702830760496630567);
+    }
+
+    struct Bytes(&'static [u8]);
+
+    impl Hash for Bytes {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            state.write(self.0);
+        }
+    }
+
+    #[test]
+    fn matches_rustc_hash_byte_vectors() {
+        let b = FxBuildHasher::default();
+        assert_eq!(b.hash_one(Bytes(&[])), 17606491139363777937);
+        assert_eq!(b.hash_one(Bytes(&[0])), 5448590020104574886);
+        assert_eq!(b.hash_one(Bytes(&[0; 6])), 16766921560080789783);
+        assert_eq!(b.hash_one(Bytes(&[1])), 5922447956811044110);
+        assert_eq!(b.hash_one(Bytes(&[2])), 5229781508510959783);
+        assert_eq!(b.hash_one(Bytes(b"uwu")), 7168164714682931527);
+        assert_eq!(
+            b.hash_one(Bytes(b"These are some bytes for testing rustc_hash.")),
+            2349210501944688211
+        );
+    }
+
+    #[test]
+    fn integer_widths_hash_the_same_value_the_same_way() {
+        let b = FxBuildHasher::default();
+        let mut a = b.build_hasher();
+        a.write_u32(7);
+        let mut c = b.build_hasher();
+        c.write_u64(7);
+        assert_eq!(a.finish(), c.finish());
+    }
+
+    #[test]
+    fn plugs_into_hash_map() {
+        let mut m = HashMap::with_hasher(FxBuildHasher::default());
+        for i in 0..1000u64 {
+            m.insert(i, i * 2);
+        }
+        assert_eq!(m.len(), 1000);
+        assert_eq!(m.get(&500), Some(&1000));
+    }
+}
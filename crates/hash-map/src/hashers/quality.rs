@@ -0,0 +1,135 @@
+use std::hash::{BuildHasher, Hash};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistributionReport {
+    pub keys: usize,
+    pub buckets: usize,
+    pub expected: f64,
+    pub chi_squared: f64,
+    pub degrees_of_freedom: usize,
+    pub max_bucket: usize,
+    pub empty_buckets: usize,
+}
+
+impl DistributionReport {
+    pub fn measure<S, T, I>(hash_builder: &S, keys: I, buckets: usize) -> Self
+    where
+        S: BuildHasher,
+        T: Hash,
+        I: IntoIterator<Item = T>,
+    {
+        assert!(buckets > 0, "bucket count must be positive");
+
+        let mut counts = vec![0usize; buckets];
+        let mut total = 0;
+        for key in keys {
+            let index = (hash_builder.hash_one(&key) as usize) % buckets;
+            counts[index] += 1;
+            total += 1;
+        }
+
+        let expected = total as f64 / buckets as f64;
+        let chi_squared = if total == 0 {
+            0.0
+        } else {
+            counts
+                .iter()
+                .map(|&observed| {
+                    let diff = observed as f64 - expected;
+                    diff * diff / expected
+                })
+                .sum()
+        };
+
+        Self {
+            keys: total,
+            buckets,
+            expected,
+            chi_squared,
+            degrees_of_freedom: buckets - 1,
+            max_bucket: counts.iter().copied().max().unwrap_or(0),
+            empty_buckets: counts.iter().filter(|&&c| c == 0).count(),
+        }
+    }
+
+    pub fn z_score(&self) -> f64 {
+        if self.degrees_of_freedom == 0 {
+            return 0.0;
+        }
+        let df = self.degrees_of_freedom as f64;
+        (self.chi_squared - df) / (2.0 * df).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{ConstantState, IdentityState};
+    use crate::hashers::{FnvBuildHasher, FxBuildHasher, SipBuildHasher};
+
+    const BUCKETS: usize = 256;
+    const KEYS: u64 = 25_600;
+
+    #[test]
+    fn empty_key_set_reports_zero_chi_squared() {
+        let r = DistributionReport::measure(&FnvBuildHasher::default(), Vec::<u64>::new(), 16);
+        assert_eq!(r.keys, 0);
+        assert_eq!(r.chi_squared, 0.0);
+        assert_eq!(r.empty_buckets, 16);
+    }
+
+    #[test]
+    fn perfectly_even_distribution_has_zero_chi_squared() {
+        let r = DistributionReport::measure(&IdentityState::default(), 0..KEYS, BUCKETS);
+        assert_eq!(r.keys, KEYS as usize);
+        assert_eq!(r.chi_squared, 0.0);
+        assert_eq!(r.max_bucket, 100);
+        assert_eq!(r.empty_buckets, 0);
+        assert!(r.z_score() < 0.0);
+    }
+
+    #[test]
+    fn single_bucket_has_zero_z_score() {
+        let r = DistributionReport::measure(&FxBuildHasher::default(), 0..KEYS, 1);
+        assert_eq!(r.degrees_of_freedom, 0);
+        assert_eq!(r.z_score(), 0.0);
+    }
+
+    #[test]
+    fn constant_hasher_is_flagged_as_terrible() {
+        let b = ConstantState::default();
+        let r = DistributionReport::measure(&b, 0..KEYS, BUCKETS);
+
+        assert_eq!(r.max_bucket, KEYS as usize);
+        assert_eq!(r.empty_buckets, BUCKETS - 1);
+        assert!(r.z_score() > 1000.0);
+    }
+
+    #[test]
+    fn sip_distributes_sequential_integers_uniformly() {
+        let r = DistributionReport::measure(&SipBuildHasher::new(1, 2), 0..KEYS, BUCKETS);
+        assert_eq!(r.keys, KEYS as usize);
+        assert_eq!(r.expected, 100.0);
+        assert!(r.z_score() < 4.0, "z = {}", r.z_score());
+    }
+
+    #[test]
+    fn fnv_distributes_strings_uniformly() {
+        let keys = (0..KEYS).map(|i| format!("user:{i}"));
+        let r = DistributionReport::measure(&FnvBuildHasher::default(), keys, BUCKETS);
+        assert!(r.z_score() < 4.0, "z = {}", r.z_score());
+    }
+
+    #[test]
+    fn fx_distributes_sequential_integers_uniformly() {
+        let r = DistributionReport::measure(&FxBuildHasher::default(), 0..KEYS, BUCKETS);
+        assert!(r.z_score() < 4.0, "z = {}", r.z_score());
+    }
+
+    #[test]
+    fn fx_distributes_strided_integers_uniformly() {
+        let keys = (0..KEYS).map(|i| i * 1024);
+        let r = DistributionReport::measure(&FxBuildHasher::default(), keys, BUCKETS);
+        assert!(r.z_score() < 4.0, "z = {}", r.z_score());
+    }
+}
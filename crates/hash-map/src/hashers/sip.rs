@@ -0,0 +1,204 @@
+use std::hash::{BuildHasher, Hasher};
+
+pub type SipHasher13 = SipHasher<1, 3>;
+pub type SipHasher24 = SipHasher<2, 4>;
+
+#[derive(Clone, Copy)]
+pub struct SipHasher<const C: usize, const D: usize> {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    tail: u64,
+    ntail: usize,
+    length: usize,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct SipBuildHasher {
+    k0: u64,
+    k1: u64,
+}
+
+impl SipBuildHasher {
+    pub fn new(k0: u64, k1: u64) -> Self {
+        Self { k0, k1 }
+    }
+
+    pub fn keys(&self) -> (u64, u64) {
+        (self.k0, self.k1)
+    }
+}
+
+impl BuildHasher for SipBuildHasher {
+    type Hasher = SipHasher13;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        SipHasher13::new_with_keys(self.k0, self.k1)
+    }
+}
+
+impl<const C: usize, const D: usize> Default for SipHasher<C, D> {
+    fn default() -> Self {
+        Self::new_with_keys(0, 0)
+    }
+}
+
+impl<const C: usize, const D: usize> SipHasher<C, D> {
+    pub fn new_with_keys(k0: u64, k1: u64) -> Self {
+        Self {
+            v0: k0 ^ 0x736f_6d65_7073_6575,
+            v1: k1 ^ 0x646f_7261_6e64_6f6d,
+            v2: k0 ^ 0x6c79_6765_6e65_7261,
+            v3: k1 ^ 0x7465_6462_7974_6573,
+            tail: 0,
+            ntail: 0,
+            length: 0,
+        }
+    }
+
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13);
+        self.v1 ^= self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16);
+        self.v3 ^= self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21);
+        self.v3 ^= self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17);
+        self.v1 ^= self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    fn compress(&mut self, m: u64) {
+        self.v3 ^= m;
+        for _ in 0..C {
+            self.round();
+        }
+        self.v0 ^= m;
+    }
+}
+
+impl<const C: usize, const D: usize> Hasher for SipHasher<C, D> {
+    fn write(&mut self, bytes: &[u8]) {
+        self.length += bytes.len();
+
+        for byte in bytes {
+            self.tail |= (*byte as u64) << (8 * self.ntail);
+            self.ntail += 1;
+
+            if self.ntail == 8 {
+                self.compress(self.tail);
+                self.tail = 0;
+                self.ntail = 0;
+            }
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let mut state = *self;
+        let b = ((self.length as u64 & 0xff) << 56) | self.tail;
+
+        state.compress(b);
+        state.v2 ^= 0xff;
+        for _ in 0..D {
+            state.round();
+        }
+
+        state.v0 ^ state.v1 ^ state.v2 ^ state.v3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{BuildHasher, Hasher};
+
+    use super::*;
+    use crate::HashMap;
+
+    const REFERENCE_K0: u64 = 0x0706_0504_0302_0100;
+    const REFERENCE_K1: u64 = 0x0f0e_0d0c_0b0a_0908;
+
+    fn sip24(len: u8) -> u64 {
+        let message: Vec<u8> = (0..len).collect();
+        let mut h = SipHasher24::new_with_keys(REFERENCE_K0, REFERENCE_K1);
+        h.write(&message);
+        h.finish()
+    }
+
+    #[test]
+    fn siphash24_matches_reference_vectors() {
+        assert_eq!(sip24(0), 0x726fdb47dd0e0e31);
+        assert_eq!(sip24(1), 0x74f839c593dc67fd);
+        assert_eq!(sip24(2), 0x0d6c8009d9a94f5a);
+        assert_eq!(sip24(3), 0x85676696d7fb7e2d);
+    }
+
+    #[test]
+    fn siphash24_matches_paper_example() {
+        assert_eq!(sip24(15), 0xa129ca6149be45e5);
+    }
+
+    #[test]
+    fn siphash13_matches_reference_vectors() {
+        let vectors: [[u8; 8]; 8] = [
+            [0xdc, 0xc4, 0x0f, 0x05, 0x58, 0x01, 0xac, 0xab],
+            [0x93, 0xca, 0x57, 0x7d, 0xf3, 0x9b, 0xf4, 0xc9],
+            [0x4d, 0xd4, 0xc7, 0x4d, 0x02, 0x9b, 0xcb, 0x82],
+            [0xfb, 0xf7, 0xdd, 0xe7, 0xb8, 0x0a, 0xf8, 0x8b],
+            [0x28, 0x83, 0xd3, 0x88, 0x60, 0x57, 0x75, 0xcf],
+            [0x67, 0x3b, 0x53, 0x49, 0x2f, 0xd5, 0xf9, 0xde],
+            [0xa7, 0x22, 0x9f, 0xc5, 0x50, 0x2b, 0x0d, 0xc5],
+            [0x40, 0x11, 0xb1, 0x9b, 0x98, 0x7d, 0x92, 0xd3],
+        ];
+
+        for (len, expected) in vectors.iter().enumerate() {
+            let message: Vec<u8> = (0..len as u8).collect();
+            let mut h = SipHasher13::new_with_keys(REFERENCE_K0, REFERENCE_K1);
+            h.write(&message);
+            assert_eq!(h.finish().to_le_bytes(), *expected, "message length {len}");
+        }
+    }
+
+    #[test]
+    fn split_writes_match_single_write() {
+        let mut a = SipHasher13::new_with_keys(1, 2);
+        a.write(b"hello world, this is sip");
+
+        let mut b = SipHasher13::new_with_keys(1, 2);
+        b.write(b"hello w");
+        b.write(b"orld, this");
+        b.write(b" is sip");
+
+        assert_eq!(a.finish(), b.finish());
+    }
+
+    #[test]
+    fn finish_does_not_consume_state() {
+        let mut h = SipHasher13::new_with_keys(1, 2);
+        h.write(b"abc");
+        assert_eq!(h.finish(), h.finish());
+    }
+
+    #[test]
+    fn different_keys_give_different_hashes() {
+        let a = SipBuildHasher::new(1, 2);
+        let b = SipBuildHasher::new(3, 4);
+        assert_ne!(a.hash_one("key"), b.hash_one("key"));
+        assert_eq!(a.hash_one("key"), a.hash_one("key"));
+    }
+
+    #[test]
+    fn plugs_into_hash_map() {
+        let mut m = HashMap::with_hasher(SipBuildHasher::new(7, 11));
+        for i in 0..100 {
+            m.insert(i, i.to_string());
+        }
+        assert_eq!(m.len(), 100);
+        assert_eq!(m.get(&7), Some(&"7".to_string()));
+    }
+}
@@ -0,0 +1,688 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+use std::{slice, vec};
+
+use crate::{DefaultHashBuilder, Entry, HashMap, RandomState};
+
+#[derive(Clone)]
+struct Slot<K, V> {
+    hash: u64,
+    key: K,
+    value: V,
+}
+
+#[derive(Clone)]
+pub struct IndexMap<K, V, S = DefaultHashBuilder> {
+    entries: Vec<Slot<K, V>>,
+    indices: HashMap<u64, Vec<usize>>,
+    hash_builder: S,
+}
+
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, Slot<K, V>>,
+}
+
+pub struct IterMut<'a, K, V> {
+    inner: slice::IterMut<'a, Slot<K, V>>,
+}
+
+pub struct IntoIter<K, V> {
+    inner: vec::IntoIter<Slot<K, V>>,
+}
+
+pub struct Keys<'a, K, V> {
+    inner: slice::Iter<'a, Slot<K, V>>,
+}
+
+pub struct Values<'a, K, V> {
+    inner: slice::Iter<'a, Slot<K, V>>,
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> Default for IndexMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K: Eq + Hash, V> IndexMap<K, V> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_hasher(RandomState::with_seed(seed))
+    }
+}
+
+impl<K, V, S> IndexMap<K, V, S> {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        self.entries.get(index).map(|s| (&s.key, &s.value))
+    }
+
+    pub fn get_index_mut(&mut self, index: usize) -> Option<(&K, &mut V)> {
+        self.entries.get_mut(index).map(|s| (&s.key, &mut s.value))
+    }
+
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.get_index(0)
+    }
+
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.get_index(self.len().checked_sub(1)?)
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            inner: self.entries.iter_mut(),
+        }
+    }
+
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys {
+            inner: self.entries.iter(),
+        }
+    }
+
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values {
+            inner: self.entries.iter(),
+        }
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> IndexMap<K, V, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+            indices: HashMap::with_capacity_and_hasher(capacity, RandomState::new()),
+            hash_builder,
+        }
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get_index_of(key).is_some()
+    }
+
+    pub fn get_index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.find(self.hash_builder.hash_one(key), key)
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index = self.get_index_of(key)?;
+        Some(&self.entries[index].value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index = self.get_index_of(key)?;
+        Some(&mut self.entries[index].value)
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.shift_remove(key)
+    }
+
+    pub fn swap_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index = self.get_index_of(key)?;
+        self.swap_remove_index(index).map(|(_, v)| v)
+    }
+
+    pub fn shift_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index = self.get_index_of(key)?;
+        self.shift_remove_index(index).map(|(_, v)| v)
+    }
+
+    pub fn swap_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        if index >= self.entries.len() {
+            return None;
+        }
+
+        let slot = self.entries.swap_remove(index);
+        self.unlink(slot.hash, index);
+        if let Some(moved) = self.entries.get(index) {
+            self.relocate(moved.hash, self.entries.len(), index);
+        }
+        Some((slot.key, slot.value))
+    }
+
+    pub fn shift_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        if index >= self.entries.len() {
+            return None;
+        }
+
+        let slot = self.entries.remove(index);
+        self.unlink(slot.hash, index);
+        for i in index..self.entries.len() {
+            self.relocate(self.entries[i].hash, i + 1, i);
+        }
+        Some((slot.key, slot.value))
+    }
+
+    pub fn move_index(&mut self, from: usize, to: usize) {
+        let len = self.entries.len();
+        assert!(
+            from < len && to < len,
+            "move_index out of bounds: from {from}, to {to}, len {len}"
+        );
+        if from == to {
+            return;
+        }
+
+        let slot = self.entries.remove(from);
+        self.unlink(slot.hash, from);
+        if from < to {
+            for i in from..to {
+                self.relocate(self.entries[i].hash, i + 1, i);
+            }
+        } else {
+            for i in (to..from).rev() {
+                self.relocate(self.entries[i].hash, i, i + 1);
+            }
+        }
+        self.link(slot.hash, to);
+        self.entries.insert(to, slot);
+    }
+
+    pub fn swap_indices(&mut self, a: usize, b: usize) {
+        self.entries.swap(a, b);
+        if a != b {
+            let (hash_a, hash_b) = (self.entries[a].hash, self.entries[b].hash);
+            self.unlink(hash_b, a);
+            self.relocate(hash_a, b, a);
+            self.link(hash_b, b);
+        }
+    }
+
+    pub fn sort_by<F>(&mut self, mut cmp: F)
+    where
+        F: FnMut(&K, &V, &K, &V) -> Ordering,
+    {
+        self.entries
+            .sort_by(|a, b| cmp(&a.key, &a.value, &b.key, &b.value));
+        self.indices.clear();
+        for i in 0..self.entries.len() {
+            self.link(self.entries[i].hash, i);
+        }
+    }
+
+    pub fn sort_keys(&mut self)
+    where
+        K: Ord,
+    {
+        self.sort_by(|k1, _, k2, _| k1.cmp(k2));
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.indices.clear();
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert_full(key, value).1
+    }
+
+    pub fn insert_full(&mut self, key: K, value: V) -> (usize, Option<V>) {
+        let hash = self.hash_builder.hash_one(&key);
+        if let Some(index) = self.find(hash, &key) {
+            let old = std::mem::replace(&mut self.entries[index].value, value);
+            return (index, Some(old));
+        }
+
+        let index = self.entries.len();
+        self.link(hash, index);
+        self.entries.push(Slot { hash, key, value });
+        (index, None)
+    }
+
+    fn find<Q>(&self, hash: u64, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq,
+    {
+        self.indices
+            .get(&hash)?
+            .iter()
+            .copied()
+            .find(|&i| self.entries[i].key.borrow() == key)
+    }
+
+    fn link(&mut self, hash: u64, index: usize) {
+        match self.indices.entry(hash) {
+            Entry::Occupied(mut entry) => entry.get_mut().push(index),
+            Entry::Vacant(entry) => {
+                entry.insert(vec![index]);
+            }
+        }
+    }
+
+    fn unlink(&mut self, hash: u64, index: usize) {
+        let positions = self
+            .indices
+            .get_mut(&hash)
+            .expect("indexed hash must be present");
+        positions.retain(|&i| i != index);
+        if positions.is_empty() {
+            self.indices.remove(&hash);
+        }
+    }
+
+    fn relocate(&mut self, hash: u64, from: usize, to: usize) {
+        let position = self
+            .indices
+            .get_mut(&hash)
+            .and_then(|positions| positions.iter_mut().find(|i| **i == from))
+            .expect("indexed position must be present");
+        *position = to;
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for IndexMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for IndexMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::with_hasher(S::default());
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Extend<(K, V)> for IndexMap<K, V, S> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K: Eq + Hash, V, const N: usize> From<[(K, V); N]> for IndexMap<K, V> {
+    fn from(pairs: [(K, V); N]) -> Self {
+        Self::from_iter(pairs)
+    }
+}
+
+impl<K, V, S> IntoIterator for IndexMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.entries.into_iter(),
+        }
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a IndexMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a mut IndexMap<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|s| (&s.key, &s.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|s| (&s.key, &mut s.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|s| (s.key, s.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|s| &s.key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|s| &s.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|s| (&s.key, &s.value))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IterMut<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|s| (&s.key, &mut s.value))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|s| (s.key, s.value))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Keys<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|s| &s.key)
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Values<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|s| &s.value)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::ConstantState;
+
+    map_test_suite!(IndexMap);
+
+    fn letters(n: usize) -> IndexMap<char, usize> {
+        ('a'..).take(n).enumerate().map(|(i, c)| (c, i)).collect()
+    }
+
+    fn keys(map: &IndexMap<char, usize>) -> String {
+        map.keys().collect()
+    }
+
+    fn assert_indices_consistent(map: &IndexMap<char, usize>) {
+        for (i, (k, _)) in map.iter().enumerate() {
+            assert_eq!(map.get_index_of(k), Some(i));
+        }
+    }
+
+    #[test]
+    fn iteration_follows_insertion_order() {
+        let mut h = IndexMap::new();
+        for k in ["zeta", "alpha", "mid", "beta"] {
+            h.insert(k, k.len());
+        }
+
+        let ks: Vec<_> = h.keys().copied().collect();
+        assert_eq!(ks, ["zeta", "alpha", "mid", "beta"]);
+        let vs: Vec<_> = h.values().rev().copied().collect();
+        assert_eq!(vs, [4, 3, 5, 4]);
+    }
+
+    #[test]
+    fn reinserting_keeps_original_position() {
+        let mut h = letters(3);
+        assert_eq!(h.insert_full('a', 10), (0, Some(0)));
+        assert_eq!(keys(&h), "abc");
+        assert_eq!(h.get_index(0), Some((&'a', &10)));
+    }
+
+    #[test]
+    fn positional_access() {
+        let mut h = letters(3);
+        assert_eq!(h.get_index(1), Some((&'b', &1)));
+        assert_eq!(h.get_index(3), None);
+        assert_eq!(h.get_index_of(&'c'), Some(2));
+        assert_eq!(h.get_index_of(&'z'), None);
+        assert_eq!(h.first(), Some((&'a', &0)));
+        assert_eq!(h.last(), Some((&'c', &2)));
+
+        *h.get_index_mut(1).unwrap().1 = 7;
+        assert_eq!(h.get(&'b'), Some(&7));
+    }
+
+    #[test]
+    fn swap_remove_moves_last_entry_into_hole() {
+        let mut h = letters(5);
+        assert_eq!(h.swap_remove(&'b'), Some(1));
+        assert_eq!(keys(&h), "aecd");
+        assert_indices_consistent(&h);
+
+        assert_eq!(h.swap_remove(&'d'), Some(3));
+        assert_eq!(keys(&h), "aec");
+        assert_eq!(h.swap_remove(&'d'), None);
+        assert_indices_consistent(&h);
+    }
+
+    #[test]
+    fn shift_remove_preserves_order() {
+        let mut h = letters(5);
+        assert_eq!(h.shift_remove(&'b'), Some(1));
+        assert_eq!(keys(&h), "acde");
+        assert_indices_consistent(&h);
+
+        assert_eq!(h.shift_remove_index(0), Some(('a', 0)));
+        assert_eq!(h.shift_remove_index(10), None);
+        assert_eq!(keys(&h), "cde");
+        assert_indices_consistent(&h);
+    }
+
+    #[test]
+    fn move_index_shifts_entries_between() {
+        let mut h = letters(5);
+        h.move_index(0, 3);
+        assert_eq!(keys(&h), "bcdae");
+        assert_indices_consistent(&h);
+
+        h.move_index(4, 1);
+        assert_eq!(keys(&h), "becda");
+        assert_indices_consistent(&h);
+
+        h.move_index(2, 2);
+        assert_eq!(keys(&h), "becda");
+    }
+
+    #[test]
+    #[should_panic(expected = "move_index out of bounds")]
+    fn move_index_panics_out_of_bounds() {
+        letters(3).move_index(0, 3);
+    }
+
+    #[test]
+    fn swap_indices_exchanges_positions() {
+        let mut h = letters(4);
+        h.swap_indices(0, 3);
+        assert_eq!(keys(&h), "dbca");
+        assert_indices_consistent(&h);
+    }
+
+    #[test]
+    fn sort_by_reorders_and_keeps_lookups() {
+        let mut h = letters(6);
+        h.sort_by(|_, v1, _, v2| v2.cmp(v1));
+        assert_eq!(keys(&h), "fedcba");
+        assert_indices_consistent(&h);
+
+        h.sort_keys();
+        assert_eq!(keys(&h), "abcdef");
+        assert_indices_consistent(&h);
+    }
+
+    #[test]
+    fn order_survives_growth() {
+        let mut h = IndexMap::new();
+        for i in (0..1000).rev() {
+            h.insert(i, i * 2);
+        }
+
+        assert_eq!(h.get_index(0), Some((&999, &1998)));
+        assert_eq!(h.get_index(999), Some((&0, &0)));
+        for i in 0..1000 {
+            assert_eq!(h.get_index_of(&i), Some(999 - i));
+        }
+    }
+
+    #[test]
+    fn constant_hasher_keeps_positions_consistent() {
+        let mut h = IndexMap::with_hasher(ConstantState::default());
+        for c in ('a'..).take(40) {
+            h.insert(c, c as usize);
+        }
+        h.swap_remove(&'c');
+        h.shift_remove(&'a');
+        h.move_index(0, 30);
+
+        assert_eq!(h.len(), 38);
+        for (i, (k, _)) in h.iter().enumerate() {
+            assert_eq!(h.get_index_of(k), Some(i));
+        }
+    }
+
+    #[test]
+    fn remove_preserves_order_like_shift_remove() {
+        let mut h = letters(5);
+        assert_eq!(h.remove(&'b'), Some(1));
+        assert_eq!(keys(&h), "acde");
+        assert_indices_consistent(&h);
+    }
+
+    #[test]
+    fn keys_without_clone_are_stored_once() {
+        #[derive(PartialEq, Eq, Hash, Debug)]
+        struct Token(u32);
+
+        let mut h: IndexMap<Token, &str> = (0..4).map(|i| (Token(i), "x")).collect();
+        assert_eq!(h.insert(Token(2), "y"), Some("x"));
+        h.swap_indices(0, 3);
+        h.move_index(3, 1);
+
+        let order: Vec<u32> = h.keys().map(|t| t.0).collect();
+        assert_eq!(order, [3, 0, 1, 2]);
+        for (i, (k, _)) in h.iter().enumerate() {
+            assert_eq!(h.get_index_of(k), Some(i));
+        }
+    }
+
+    #[test]
+    fn into_iter_and_iter_mut_follow_order() {
+        let mut h = letters(3);
+        for (_, v) in h.iter_mut() {
+            *v *= 10;
+        }
+
+        let pairs: Vec<_> = h.into_iter().collect();
+        assert_eq!(pairs, [('a', 0), ('b', 10), ('c', 20)]);
+    }
+
+    #[test]
+    fn debug_prints_in_insertion_order() {
+        let h = IndexMap::from([("b", 1), ("a", 2)]);
+        assert_eq!(format!("{h:?}"), r#"{"b": 1, "a": 2}"#);
+    }
+}
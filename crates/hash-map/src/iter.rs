@@ -0,0 +1,628 @@
+use std::iter::{Chain, FusedIterator};
+use std::{slice, vec};
+
+use crate::HashMap;
+use crate::bucket::Bucket;
+
+type BucketsIter<'a, K, V> = slice::Iter<'a, Bucket<K, V>>;
+
+pub struct Iter<'a, K, V> {
+    buckets: Chain<BucketsIter<'a, K, V>, BucketsIter<'a, K, V>>,
+    current: slice::Iter<'a, (K, V)>,
+    remaining: usize,
+}
+
+pub struct IterMut<'a, K, V> {
+    buckets: slice::IterMut<'a, Bucket<K, V>>,
+    current: slice::IterMut<'a, (K, V)>,
+    remaining: usize,
+}
+
+pub struct IntoIter<K, V> {
+    buckets: vec::IntoIter<Bucket<K, V>>,
+    current: vec::IntoIter<(K, V)>,
+    remaining: usize,
+}
+
+pub struct Drain<'a, K, V> {
+    buckets: slice::IterMut<'a, Bucket<K, V>>,
+    current: Option<vec::Drain<'a, (K, V)>>,
+    remaining: usize,
+}
+
+pub struct ExtractIf<'a, K, V, F>
+where
+    F: FnMut(&K, &mut V) -> bool,
+{
+    buckets: &'a mut [Bucket<K, V>],
+    counter: &'a mut usize,
+    bucket: usize,
+    pos: usize,
+    pred: F,
+}
+
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+pub struct IntoKeys<K, V> {
+    inner: IntoIter<K, V>,
+}
+
+pub struct IntoValues<K, V> {
+    inner: IntoIter<K, V>,
+}
+
+impl<K, V, S> HashMap<K, V, S> {
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let old: &[Bucket<K, V>] = match &self.migration {
+            Some(migration) => &migration.buckets,
+            None => &[],
+        };
+
+        Iter {
+            buckets: self.buckets.iter().chain(old.iter()),
+            current: [].iter(),
+            remaining: self.counter,
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        self.finish_resize();
+        IterMut {
+            buckets: self.buckets.iter_mut(),
+            current: [].iter_mut(),
+            remaining: self.counter,
+        }
+    }
+
+    pub fn drain(&mut self) -> Drain<'_, K, V> {
+        self.finish_resize();
+        let remaining = self.counter;
+        self.counter = 0;
+
+        Drain {
+            buckets: self.buckets.iter_mut(),
+            current: None,
+            remaining,
+        }
+    }
+
+    pub fn extract_if<F>(&mut self, pred: F) -> ExtractIf<'_, K, V, F>
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.finish_resize();
+        ExtractIf {
+            buckets: &mut self.buckets,
+            counter: &mut self.counter,
+            bucket: 0,
+            pos: 0,
+            pred,
+        }
+    }
+
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut {
+            inner: self.iter_mut(),
+        }
+    }
+
+    pub fn into_keys(self) -> IntoKeys<K, V> {
+        IntoKeys {
+            inner: self.into_iter(),
+        }
+    }
+
+    pub fn into_values(self) -> IntoValues<K, V> {
+        IntoValues {
+            inner: self.into_iter(),
+        }
+    }
+}
+
+impl<K, V, S> IntoIterator for HashMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        self.finish_resize();
+        IntoIter {
+            buckets: self.buckets.into_iter(),
+            current: Vec::new().into_iter(),
+            remaining: self.counter,
+        }
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a HashMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a mut HashMap<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((k, v)) = self.current.next() {
+                self.remaining -= 1;
+                return Some((k, v));
+            }
+            self.current = self.buckets.next()?.entries().iter();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((k, v)) = self.current.next() {
+                self.remaining -= 1;
+                return Some((&*k, v));
+            }
+            self.current = self.buckets.next()?.entries_mut().iter_mut();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pair) = self.current.next() {
+                self.remaining -= 1;
+                return Some(pair);
+            }
+            self.current = self.buckets.next()?.into_entries().into_iter();
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> Iterator for Drain<'_, K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(pair) = self.current.as_mut().and_then(Iterator::next) {
+                self.remaining -= 1;
+                return Some(pair);
+            }
+            self.current = Some(self.buckets.next()?.drain());
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> Drop for Drain<'_, K, V> {
+    fn drop(&mut self) {
+        for bucket in self.buckets.by_ref() {
+            bucket.clear();
+        }
+    }
+}
+
+impl<K, V, F> Iterator for ExtractIf<'_, K, V, F>
+where
+    F: FnMut(&K, &mut V) -> bool,
+{
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.bucket < self.buckets.len() {
+            let bucket = &mut self.buckets[self.bucket];
+
+            while self.pos < bucket.len() {
+                let (k, v) = &mut bucket.entries_mut()[self.pos];
+                if (self.pred)(k, v) {
+                    *self.counter -= 1;
+                    return Some(bucket.remove(self.pos));
+                }
+                self.pos += 1;
+            }
+
+            self.bucket += 1;
+            self.pos = 0;
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(*self.counter))
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> Iterator for IntoKeys<K, V> {
+    type Item = K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> Iterator for IntoValues<K, V> {
+    type Item = V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+impl<K, V> ExactSizeIterator for Drain<'_, K, V> {}
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+impl<K, V> ExactSizeIterator for ValuesMut<'_, K, V> {}
+impl<K, V> ExactSizeIterator for IntoKeys<K, V> {}
+impl<K, V> ExactSizeIterator for IntoValues<K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+impl<K, V> FusedIterator for IterMut<'_, K, V> {}
+impl<K, V> FusedIterator for IntoIter<K, V> {}
+impl<K, V> FusedIterator for Drain<'_, K, V> {}
+impl<K, V, F> FusedIterator for ExtractIf<'_, K, V, F> where F: FnMut(&K, &mut V) -> bool {}
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+impl<K, V> FusedIterator for ValuesMut<'_, K, V> {}
+impl<K, V> FusedIterator for IntoKeys<K, V> {}
+impl<K, V> FusedIterator for IntoValues<K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::HashMap;
+
+    fn sample(n: usize) -> HashMap<String, usize> {
+        let mut h = HashMap::new();
+        for i in 0..n {
+            h.insert(format!("k{i}"), i);
+        }
+        h
+    }
+
+    fn sorted<T: Ord>(mut items: Vec<T>) -> Vec<T> {
+        items.sort();
+        items
+    }
+
+    #[test]
+    fn iter_on_empty_map_yields_nothing() {
+        let h = HashMap::<String, usize>::new();
+        assert_eq!(h.iter().next(), None);
+        assert_eq!(h.iter().len(), 0);
+    }
+
+    #[test]
+    fn iter_yields_every_pair_once() {
+        let h = sample(100);
+        let pairs = sorted(h.iter().map(|(k, v)| (k.clone(), *v)).collect());
+        let expected = sorted((0..100).map(|i| (format!("k{i}"), i)).collect());
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn iter_size_hint_is_exact_and_decreases() {
+        let h = sample(10);
+        let mut it = h.iter();
+        for remaining in (0..10).rev() {
+            assert!(it.next().is_some());
+            assert_eq!(it.size_hint(), (remaining, Some(remaining)));
+            assert_eq!(it.len(), remaining);
+        }
+    }
+
+    #[test]
+    fn iter_is_fused() {
+        let h = sample(3);
+        let mut it = h.iter();
+        for _ in 0..3 {
+            assert!(it.next().is_some());
+        }
+        assert!(it.next().is_none());
+        assert!(it.next().is_none());
+    }
+
+    #[test]
+    fn iter_mut_allows_updating_values() {
+        let mut h = sample(50);
+        for (_, v) in h.iter_mut() {
+            *v *= 2;
+        }
+        for i in 0..50 {
+            assert_eq!(h.get(format!("k{i}").as_str()), Some(&(i * 2)));
+        }
+        assert_eq!(h.len(), 50);
+    }
+
+    #[test]
+    fn keys_yields_every_key() {
+        let h = sample(20);
+        let keys = sorted(h.keys().cloned().collect());
+        let expected = sorted((0..20).map(|i| format!("k{i}")).collect());
+        assert_eq!(keys, expected);
+        assert_eq!(h.keys().len(), 20);
+    }
+
+    #[test]
+    fn values_yields_every_value() {
+        let h = sample(20);
+        let values = sorted(h.values().copied().collect());
+        assert_eq!(values, (0..20).collect::<Vec<_>>());
+        assert_eq!(h.values().len(), 20);
+    }
+
+    #[test]
+    fn values_mut_allows_updating_values() {
+        let mut h = sample(20);
+        for v in h.values_mut() {
+            *v += 1;
+        }
+        let values = sorted(h.values().copied().collect());
+        assert_eq!(values, (1..21).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn into_iter_yields_owned_pairs() {
+        let h = sample(30);
+        let it = h.into_iter();
+        assert_eq!(it.len(), 30);
+        let pairs = sorted(it.collect());
+        let expected = sorted((0..30).map(|i| (format!("k{i}"), i)).collect());
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn into_keys_and_into_values_yield_owned_items() {
+        let keys = sorted(sample(5).into_keys().collect());
+        assert_eq!(keys, vec!["k0", "k1", "k2", "k3", "k4"]);
+
+        let values = sorted(sample(5).into_values().collect());
+        assert_eq!(values, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn for_loops_work_on_map_references() {
+        let mut h = sample(10);
+
+        let mut total = 0;
+        for (_, v) in &h {
+            total += v;
+        }
+        assert_eq!(total, 45);
+
+        for (_, v) in &mut h {
+            *v = 0;
+        }
+        assert!(h.values().all(|v| *v == 0));
+
+        let mut count = 0;
+        for (_, _) in h {
+            count += 1;
+        }
+        assert_eq!(count, 10);
+    }
+
+    #[test]
+    fn iter_reflects_removals_and_clear() {
+        let mut h = sample(10);
+        h.remove("k3");
+        h.remove("k7");
+        assert_eq!(h.iter().len(), 8);
+        assert!(h.keys().all(|k| k != "k3" && k != "k7"));
+
+        h.clear();
+        assert_eq!(h.iter().len(), 0);
+        assert_eq!(h.iter().next(), None);
+    }
+
+    #[test]
+    fn drain_yields_every_pair_and_empties_map() {
+        let mut h = sample(40);
+        let drained = sorted(h.drain().collect());
+        let expected = sorted((0..40).map(|i| (format!("k{i}"), i)).collect());
+
+        assert_eq!(drained, expected);
+        assert!(h.is_empty());
+        assert_eq!(h.iter().next(), None);
+    }
+
+    #[test]
+    fn drain_size_hint_is_exact() {
+        let mut h = sample(5);
+        let mut it = h.drain();
+        assert_eq!(it.len(), 5);
+        it.next();
+        assert_eq!(it.len(), 4);
+    }
+
+    #[test]
+    fn drain_dropped_early_still_empties_map() {
+        let mut h = sample(40);
+        {
+            let mut it = h.drain();
+            it.next();
+            it.next();
+        }
+
+        assert_eq!(h.len(), 0);
+        assert!(h.is_empty());
+        assert_eq!(h.iter().count(), 0);
+        assert!(!h.contains("k0"));
+    }
+
+    #[test]
+    fn map_is_usable_after_drain() {
+        let mut h = sample(40);
+        h.drain();
+
+        h.insert("x".to_string(), 1);
+        assert_eq!(h.len(), 1);
+        assert_eq!(h.get("x"), Some(&1));
+    }
+
+    #[test]
+    fn retain_keeps_only_matching_pairs() {
+        let mut h = sample(100);
+        h.retain(|_, v| *v % 2 == 0);
+
+        assert_eq!(h.len(), 50);
+        assert_eq!(h.iter().count(), 50);
+        for i in 0..100 {
+            assert_eq!(h.contains(format!("k{i}").as_str()), i % 2 == 0);
+        }
+    }
+
+    #[test]
+    fn retain_can_mutate_kept_values() {
+        let mut h = sample(10);
+        h.retain(|_, v| {
+            *v += 100;
+            *v < 105
+        });
+
+        assert_eq!(h.len(), 5);
+        assert_eq!(h.get("k0"), Some(&100));
+        assert!(!h.contains("k5"));
+    }
+
+    #[test]
+    fn retain_removing_everything_empties_map() {
+        let mut h = sample(10);
+        h.retain(|_, _| false);
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn extract_if_removes_and_yields_matching_pairs() {
+        let mut h = sample(100);
+        let extracted = sorted(h.extract_if(|_, v| *v >= 90).map(|(_, v)| v).collect());
+
+        assert_eq!(extracted, (90..100).collect::<Vec<_>>());
+        assert_eq!(h.len(), 90);
+        assert_eq!(h.iter().count(), 90);
+        assert!(!h.contains("k95"));
+        assert_eq!(h.get("k5"), Some(&5));
+    }
+
+    #[test]
+    fn extract_if_dropped_early_keeps_unvisited_pairs() {
+        let mut h = sample(100);
+        {
+            let mut it = h.extract_if(|_, _| true);
+            it.next();
+            it.next();
+            it.next();
+        }
+
+        assert_eq!(h.len(), 97);
+        assert_eq!(h.iter().count(), 97);
+    }
+
+    #[test]
+    fn extract_if_is_lazy() {
+        let mut h = sample(10);
+        let mut calls = 0;
+        {
+            let _it = h.extract_if(|_, _| {
+                calls += 1;
+                true
+            });
+        }
+
+        assert_eq!(calls, 0);
+        assert_eq!(h.len(), 10);
+    }
+}
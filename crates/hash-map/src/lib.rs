@@ -1,26 +1,105 @@
-use std::hash::{DefaultHasher, Hash, Hasher};
+#[cfg(test)]
+#[macro_use]
+mod test_suite;
+#[cfg(test)]
+mod fixtures;
+
+mod bucket;
+mod capacity;
+mod counter;
+mod cuckoo;
+mod entry;
+mod iter;
+mod random_state;
+mod resize;
+mod robin_hood;
+mod swiss;
+mod traits;
+
+pub mod bi_map;
+pub mod concurrent;
+pub mod hashers;
+pub mod index_map;
+pub mod linked_hash_map;
+pub mod multi_map;
+
+pub use bi_map::{BiMap, Overwritten};
+pub use capacity::{GrowthPolicy, HashMapBuilder, TryReserveError};
+pub use concurrent::ConcurrentHashMap;
+pub use counter::Counter;
+pub use cuckoo::CuckooMap;
+pub use entry::{Entry, OccupiedEntry, OccupiedError, VacantEntry};
+pub use index_map::IndexMap;
+pub use iter::{
+    Drain, ExtractIf, IntoIter, IntoKeys, IntoValues, Iter, IterMut, Keys, Values, ValuesMut,
+};
+pub use linked_hash_map::{LinkOrder, LinkedHashMap};
+pub use multi_map::MultiMap;
+pub use random_state::RandomState;
+pub use resize::ResizeMode;
+pub use robin_hood::RobinHoodMap;
+pub use swiss::SwissMap;
+
+use bucket::{Bucket, KeyTag};
+use resize::Migration;
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
 
 const HASH_MAP_INITIAL_CAPACITY: usize = 16;
 
-pub struct HashMap<K, V> {
-    buckets: Vec<Vec<(K, V)>>,
+pub type DefaultHashBuilder = RandomState;
+
+#[derive(Clone)]
+pub struct HashMap<K, V, S = DefaultHashBuilder> {
+    buckets: Vec<Bucket<K, V>>,
     counter: usize,
     capacity: usize,
+    hash_builder: S,
+    resize_mode: ResizeMode,
+    migration: Option<Migration<K, V>>,
+    max_load_factor: f64,
+    growth_policy: GrowthPolicy,
+    key_tag: KeyTag<K>,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct Location {
+    migrating: bool,
+    bucket: usize,
+    pos: usize,
 }
 
-impl<K: Eq + Hash, V> Default for HashMap<K, V> {
+impl<K: Eq + Hash, V, S: BuildHasher + Default> Default for HashMap<K, V, S> {
     fn default() -> Self {
-        Self::new()
+        Self::with_hasher(S::default())
     }
 }
 
 impl<K: Eq + Hash, V> HashMap<K, V> {
     pub fn new() -> Self {
-        Self {
-            buckets: (0..HASH_MAP_INITIAL_CAPACITY).map(|_| Vec::new()).collect(),
-            counter: 0,
-            capacity: HASH_MAP_INITIAL_CAPACITY,
-        }
+        Self::with_hasher(RandomState::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_hasher(RandomState::with_seed(seed))
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> HashMap<K, V, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        HashMapBuilder::new()
+            .capacity(capacity)
+            .hasher(hash_builder)
+            .build()
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
     }
 
     pub fn len(&self) -> usize {
@@ -31,344 +110,607 @@ impl<K: Eq + Hash, V> HashMap<K, V> {
         self.counter == 0
     }
 
-    pub fn contains(&self, key: K) -> bool {
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
         self.get(key).is_some()
     }
 
-    pub fn get(&self, key: K) -> Option<&V> {
-        let index = self.bucket_index(&key);
-        let bucket = &self.buckets[index];
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get_key_value(key).map(|(_, v)| v)
+    }
+
+    pub fn get_key_value<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.locate(key).map(|location| self.pair_at(location))
+    }
+
+    pub(crate) fn locate<Q>(&self, key: &Q) -> Option<Location>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.locate_by(key, |k| k.borrow() == key)
+    }
+
+    pub(crate) fn locate_by<Q, F>(&self, key: &Q, eq: F) -> Option<Location>
+    where
+        Q: ?Sized + Hash,
+        F: Fn(&K) -> bool,
+    {
+        let hash = self.hash(key);
+        let bucket = self.bucket_index(hash);
+
+        if let Some(pos) = self.buckets[bucket].find_by(hash, key, &eq) {
+            return Some(Location {
+                migrating: false,
+                bucket,
+                pos,
+            });
+        }
 
-        for (k, v) in bucket {
-            if k == &key {
-                return Some(v);
+        let migration = self.migration.as_ref()?;
+        let bucket = Self::bucket_index_for(hash, migration.capacity);
+        let pos = migration.buckets[bucket].find_by(hash, key, &eq)?;
+        Some(Location {
+            migrating: true,
+            bucket,
+            pos,
+        })
+    }
+
+    pub(crate) fn pair_at(&self, location: Location) -> (&K, &V) {
+        let buckets = match (location.migrating, &self.migration) {
+            (true, Some(migration)) => &migration.buckets,
+            _ => &self.buckets,
+        };
+
+        let (k, v) = &buckets[location.bucket].entries()[location.pos];
+        (k, v)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let hash = self.hash(key);
+        self.migrate_key(hash, key);
+        let index = self.bucket_index(hash);
+        let bucket = &mut self.buckets[index];
+
+        let pos = bucket.find(hash, key)?;
+        Some(&mut bucket.entries_mut()[pos].1)
+    }
+
+    pub fn get_many_mut<Q, const N: usize>(&mut self, keys: [&Q; N]) -> Option<[&mut V; N]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let hashes = keys.map(|key| self.hash(key));
+        for (key, hash) in keys.iter().zip(hashes) {
+            self.migrate_key(hash, *key);
+        }
+
+        let mut locations = Vec::with_capacity(N);
+        for (slot, (key, hash)) in keys.iter().zip(hashes).enumerate() {
+            let index = self.bucket_index(hash);
+            let pos = self.buckets[index].find(hash, *key)?;
+            locations.push((index, pos, slot));
+        }
+
+        locations.sort_unstable();
+        if locations
+            .windows(2)
+            .any(|w| (w[0].0, w[0].1) == (w[1].0, w[1].1))
+        {
+            return None;
+        }
+
+        let mut found: [Option<&mut V>; N] = [const { None }; N];
+        let mut buckets: &mut [Bucket<K, V>] = &mut self.buckets;
+        let mut bucket_offset = 0;
+        let mut i = 0;
+
+        while i < locations.len() {
+            let index = locations[i].0;
+            let (bucket, rest) =
+                std::mem::take(&mut buckets)[index - bucket_offset..].split_first_mut()?;
+            buckets = rest;
+            bucket_offset = index + 1;
+
+            let mut entries = bucket.entries_mut();
+            let mut entry_offset = 0;
+            while i < locations.len() && locations[i].0 == index {
+                let (_, pos, slot) = locations[i];
+                let (entry, rest) =
+                    std::mem::take(&mut entries)[pos - entry_offset..].split_first_mut()?;
+                entries = rest;
+                entry_offset = pos + 1;
+                found[slot] = Some(&mut entry.1);
+                i += 1;
             }
         }
 
-        None
+        Some(found.map(|v| v.expect("every requested key was located")))
     }
 
-    pub fn insert(&mut self, key: K, value: V) {
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
         if self.should_resize() {
             self.resize();
         }
+        self.migrate_step();
 
-        let index = self.bucket_index(&key);
+        let hash = self.hash(&key);
+        self.migrate_key(hash, &key);
+        let index = self.bucket_index(hash);
         let bucket = &mut self.buckets[index];
 
-        for (k, v) in bucket.iter_mut() {
-            if k == &key {
-                let _ = std::mem::replace(v, value);
-                return;
-            }
+        if let Some(pos) = bucket.find(hash, &key) {
+            return Some(std::mem::replace(&mut bucket.entries_mut()[pos].1, value));
         }
 
-        bucket.push((key, value));
+        bucket.insert(hash, key, value, self.key_tag);
         self.counter += 1;
+        None
+    }
+
+    pub fn try_insert(&mut self, key: K, value: V) -> Result<&mut V, OccupiedError<'_, K, V>> {
+        match self.entry(key) {
+            Entry::Occupied(entry) => Err(OccupiedError { entry, value }),
+            Entry::Vacant(entry) => Ok(entry.insert(value)),
+        }
     }
 
-    pub fn remove(&mut self, key: K) -> Option<V> {
-        let index = self.bucket_index(&key);
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
+        if self.should_resize() {
+            self.resize();
+        }
+        self.migrate_step();
+
+        let hash = self.hash(&key);
+        self.migrate_key(hash, &key);
+        let index = self.bucket_index(hash);
+
         let bucket = &mut self.buckets[index];
+        let counter = &mut self.counter;
 
-        if let Some(pos) = bucket.iter().position(|(k, _)| k == &key) {
-            let (_, v) = bucket.remove(pos);
-            self.counter -= 1;
-            return Some(v);
+        match bucket.find(hash, &key) {
+            Some(pos) => Entry::occupied(bucket, counter, pos),
+            None => Entry::vacant(bucket, counter, hash, key, self.key_tag),
         }
+    }
 
-        None
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.remove_entry_by(key, |k| k.borrow() == key)
+    }
+
+    pub(crate) fn remove_entry_by<Q, F>(&mut self, key: &Q, eq: F) -> Option<(K, V)>
+    where
+        Q: ?Sized + Hash,
+        F: Fn(&K) -> bool,
+    {
+        self.migrate_step();
+
+        let hash = self.hash(key);
+        self.migrate_key_by(hash, key, &eq);
+        let index = self.bucket_index(hash);
+        let bucket = &mut self.buckets[index];
+
+        let pos = bucket.find_by(hash, key, &eq)?;
+        self.counter -= 1;
+        Some(bucket.remove(pos))
     }
 
     pub fn clear(&mut self) {
+        self.migration = None;
         for bucket in self.buckets.iter_mut() {
             bucket.clear();
         }
         self.counter = 0;
     }
 
-    fn bucket_index(&self, key: &K) -> usize {
-        let mut hasher = DefaultHasher::new();
-        key.hash(&mut hasher);
-        (hasher.finish() as usize) % self.capacity
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &mut V) -> bool,
+    {
+        self.finish_resize();
+        for bucket in self.buckets.iter_mut() {
+            let before = bucket.len();
+            bucket.retain(&mut f);
+            self.counter -= before - bucket.len();
+        }
     }
 
-    fn bucket_index_for(key: &K, capacity: usize) -> usize {
-        let mut hasher = DefaultHasher::new();
-        key.hash(&mut hasher);
-        (hasher.finish() as usize) % capacity
+    fn hash<Q: ?Sized + Hash>(&self, key: &Q) -> u64 {
+        self.hash_builder.hash_one(key)
     }
 
-    fn should_resize(&self) -> bool {
-        !self.buckets.is_empty() && self.counter * 4 >= self.buckets.len() * 3
+    fn bucket_index(&self, hash: u64) -> usize {
+        Self::bucket_index_for(hash, self.capacity)
     }
 
-    fn resize(&mut self) {
-        let new_capacity = self.capacity * 2;
-        let mut new_buckets: Vec<Vec<(K, V)>> = (0..new_capacity).map(|_| Vec::new()).collect();
+    fn bucket_index_for(hash: u64, capacity: usize) -> usize {
+        (hash as usize) % capacity
+    }
 
-        for bucket in self.buckets.iter_mut() {
-            for (k, v) in bucket.drain(..) {
-                let index = Self::bucket_index_for(&k, new_capacity);
-                new_buckets[index].push((k, v));
-            }
+    fn migrate_key<Q>(&mut self, hash: u64, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.migrate_key_by(hash, key, |k| k.borrow() == key);
+    }
+
+    fn migrate_key_by<Q, F>(&mut self, hash: u64, key: &Q, eq: F)
+    where
+        Q: ?Sized + Hash,
+        F: Fn(&K) -> bool,
+    {
+        let Some(migration) = self.migration.as_mut() else {
+            return;
+        };
+
+        let bucket = &mut migration.buckets[Self::bucket_index_for(hash, migration.capacity)];
+        if let Some(pos) = bucket.find_by(hash, key, eq) {
+            let (k, v) = bucket.remove(pos);
+            let index = Self::bucket_index_for(hash, self.capacity);
+            self.buckets[index].insert(hash, k, v, self.key_tag);
         }
+    }
 
-        self.buckets = new_buckets;
-        self.capacity = new_capacity;
+    fn should_resize(&self) -> bool {
+        !self.buckets.is_empty()
+            && self.counter as f64 >= self.capacity as f64 * self.max_load_factor
+    }
+
+    fn resize(&mut self) {
+        let new_capacity = self
+            .growth_policy
+            .next(self.capacity)
+            .expect("capacity overflow");
+        self.start_resize(new_capacity);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fixtures::{ConstantState, IdentityState};
+
+    map_test_suite!(HashMap);
 
     #[test]
-    fn len_returns_zero_for_new_hash_map() {
-        let h = HashMap::<String, String>::new();
-        assert_eq!(h.len(), 0);
+    fn with_hasher_uses_given_hasher_for_placement() {
+        let mut h = HashMap::with_hasher(IdentityState::default());
+        for i in 0..10u64 {
+            h.insert(i, i * 10);
+        }
+
+        for i in 0..10u64 {
+            assert_eq!(h.buckets[i as usize].entries(), &[(i, i * 10)]);
+        }
     }
 
     #[test]
-    fn is_empty_returns_true_for_new_hash_map() {
-        let h = HashMap::<String, String>::new();
-        assert!(h.is_empty());
+    fn constant_hasher_puts_every_key_in_one_bucket_but_stays_correct() {
+        let mut h = HashMap::with_hasher(ConstantState::default());
+        for i in 0..100 {
+            h.insert(i, i);
+        }
+
+        assert_eq!(h.len(), 100);
+        assert_eq!(h.buckets[0].len(), 100);
+        for i in 0..100 {
+            assert_eq!(h.get(&i), Some(&i));
+        }
+        assert_eq!(h.remove(&50), Some(50));
+        assert!(!h.contains(&50));
     }
 
     #[test]
-    fn insert_increases_len_from_zero_to_one() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
-        assert_eq!(h.len(), 1);
-        assert!(!h.is_empty());
+    fn constant_hasher_lookups_probe_sub_linearly() {
+        let mut h = HashMap::with_hasher(ConstantState::default());
+        for i in 0..4096 {
+            h.insert(i, i);
+        }
+        for i in (0..4096).step_by(2) {
+            h.remove(&i);
+        }
+
+        let bucket = &h.buckets[0];
+        assert_eq!(bucket.len(), 2048);
+        let worst = (0..4096).map(|i| bucket.probes(0, &i)).max().unwrap();
+        assert!(worst <= 16, "worst lookup took {worst} probes");
     }
 
     #[test]
-    fn insert_multiple_distinct_keys_increases_len_each_time() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
-        h.insert("b".to_string(), "2".to_string());
-        h.insert("c".to_string(), "3".to_string());
-        assert_eq!(h.len(), 3);
+    fn with_capacity_and_hasher_avoids_resize_for_requested_capacity() {
+        let mut h = HashMap::with_capacity_and_hasher(1000, DefaultHashBuilder::default());
+        let capacity = h.capacity;
+        assert!(capacity * 3 >= 1000 * 4);
+
+        for i in 0..1000 {
+            h.insert(i, i);
+        }
+        assert_eq!(h.capacity, capacity);
     }
 
     #[test]
-    fn insert_same_key_twice_does_not_increase_len() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
-        h.insert("a".to_string(), "2".to_string());
-        assert_eq!(h.len(), 1);
+    fn with_capacity_and_hasher_zero_keeps_initial_capacity() {
+        let h = HashMap::<i32, i32>::with_capacity_and_hasher(0, DefaultHashBuilder::default());
+        assert_eq!(h.capacity, HASH_MAP_INITIAL_CAPACITY);
+        assert_eq!(h.buckets.len(), h.capacity);
     }
 
     #[test]
-    fn insert_same_key_many_times_keeps_len_one() {
-        let mut h = HashMap::<String, String>::new();
-        for i in 0..50 {
-            h.insert("a".to_string(), i.to_string());
-        }
-        assert_eq!(h.len(), 1);
-        assert!(!h.is_empty());
+    fn default_with_custom_hasher_builds_empty_map() {
+        let h: HashMap<i32, i32, IdentityState> = HashMap::default();
+        assert!(h.is_empty());
+        assert!(h.get(&1).is_none());
     }
 
     #[test]
-    fn insert_many_keys_len_matches_count() {
-        let mut h = HashMap::<String, String>::new();
+    fn maps_with_same_seed_lay_out_keys_identically() {
+        let mut a = HashMap::with_seed(42);
+        let mut b = HashMap::with_seed(42);
         for i in 0..100 {
-            h.insert(format!("k{i}"), format!("v{i}"));
+            a.insert(i, i);
+            b.insert(i, i);
         }
-        assert_eq!(h.len(), 100);
+        assert_eq!(a.buckets, b.buckets);
     }
 
     #[test]
-    fn get_returns_none_for_empty_hash_map() {
-        let h = HashMap::<String, String>::new();
-        assert!(h.get("a".to_string()).is_none());
+    fn new_maps_are_seeded_independently() {
+        let a = HashMap::<u64, u64>::new();
+        let b = HashMap::<u64, u64>::new();
+        assert_ne!(a.hasher().keys(), b.hasher().keys());
     }
 
     #[test]
-    fn get_returns_some_after_insert() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
-        assert_eq!(h.get("a".to_string()), Some(&"1".to_string()));
+    fn keys_colliding_in_one_map_spread_out_in_another() {
+        let victim = HashMap::<u64, ()>::new();
+        let colliding: Vec<u64> = (0..100_000u64)
+            .filter(|k| victim.bucket_index(victim.hash(k)) == 0)
+            .take(64)
+            .collect();
+        assert_eq!(colliding.len(), 64);
+
+        let mut other = HashMap::<u64, ()>::new();
+        for k in &colliding {
+            other.insert(*k, ());
+        }
+        let longest = other.buckets.iter().map(Bucket::len).max().unwrap();
+        assert!(longest < 32, "longest chain = {longest}");
     }
 
-    #[test]
-    fn get_returns_none_for_missing_key() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
-        assert!(h.get("b".to_string()).is_none());
+    fn colliding_map(n: u64) -> HashMap<u64, u64, IdentityState> {
+        let mut h = HashMap::with_hasher(IdentityState::default());
+        for i in 0..n {
+            h.insert(i << 32, i);
+        }
+        h
     }
 
     #[test]
-    fn get_returns_updated_value_for_same_key() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
-        h.insert("a".to_string(), "2".to_string());
-        assert_eq!(h.get("a".to_string()), Some(&"2".to_string()));
+    fn long_collision_chain_is_treeified() {
+        let h = colliding_map(100);
+        assert_eq!(h.buckets[0].len(), 100);
+        assert!(h.buckets[0].is_ordered());
+        assert!(h.buckets[1..].iter().all(|b| b.len() == 0));
     }
 
     #[test]
-    fn get_works_for_many_keys() {
-        let mut h = HashMap::<String, String>::new();
+    fn treeified_bucket_supports_get_insert_and_remove() {
+        let mut h = colliding_map(100);
+
         for i in 0..100 {
-            h.insert(format!("k{i}"), format!("v{i}"));
+            assert_eq!(h.get(&(i << 32)), Some(&i));
         }
-        assert_eq!(h.get("k0".to_string()), Some(&"v0".to_string()));
-        assert_eq!(h.get("k50".to_string()), Some(&"v50".to_string()));
-        assert_eq!(h.get("k99".to_string()), Some(&"v99".to_string()));
-        assert!(h.get("k100".to_string()).is_none());
-    }
+        assert!(!h.contains(&(100 << 32)));
 
-    #[test]
-    fn remove_returns_none_for_empty_hash_map() {
-        let mut h = HashMap::<String, String>::new();
-        assert!(h.remove("a".to_string()).is_none());
-        assert_eq!(h.len(), 0);
-        assert!(h.is_empty());
+        h.insert(5 << 32, 500);
+        assert_eq!(h.get(&(5 << 32)), Some(&500));
+        assert_eq!(h.len(), 100);
+
+        for i in 0..50 {
+            assert_eq!(h.remove(&(i << 32)), Some(if i == 5 { 500 } else { i }));
+        }
+        assert_eq!(h.len(), 50);
+        for i in 50..100 {
+            assert_eq!(h.get(&(i << 32)), Some(&i));
+        }
     }
 
     #[test]
-    fn remove_returns_some_and_decreases_len() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
+    fn treeified_bucket_is_untreeified_when_it_shrinks() {
+        let mut h = colliding_map(20);
+        assert!(h.buckets[0].is_ordered());
 
-        let removed = h.remove("a".to_string());
-        assert_eq!(removed, Some("1".to_string()));
-        assert_eq!(h.len(), 0);
-        assert!(h.is_empty());
+        for i in 0..15 {
+            h.remove(&(i << 32));
+        }
+        assert_eq!(h.buckets[0].len(), 5);
+        assert!(!h.buckets[0].is_ordered());
+
+        for i in 15..20 {
+            assert_eq!(h.get(&(i << 32)), Some(&i));
+        }
     }
 
     #[test]
-    fn remove_missing_key_returns_none_and_len_unchanged() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
-        h.insert("b".to_string(), "2".to_string());
+    fn entry_api_works_on_treeified_bucket() {
+        let mut h = colliding_map(50);
 
-        let removed = h.remove("c".to_string());
-        assert!(removed.is_none());
-        assert_eq!(h.len(), 2);
+        *h.entry(7 << 32).or_insert(0) += 1000;
+        *h.entry(99 << 32).or_insert(0) += 1;
+
+        assert_eq!(h.get(&(7 << 32)), Some(&1007));
+        assert_eq!(h.get(&(99 << 32)), Some(&1));
+        assert_eq!(h.len(), 51);
+        assert!(h.buckets[0].is_ordered());
     }
 
     #[test]
-    fn remove_then_get_returns_none() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
+    fn retain_and_iteration_work_on_treeified_bucket() {
+        let mut h = colliding_map(40);
+        h.retain(|_, v| *v % 2 == 0);
 
-        assert!(h.remove("a".to_string()).is_some());
-        assert!(h.get("a".to_string()).is_none());
-        assert_eq!(h.len(), 0);
+        assert_eq!(h.len(), 20);
+        assert_eq!(h.iter().count(), 20);
+        assert!(h.values().all(|v| v % 2 == 0));
     }
 
     #[test]
-    fn remove_only_removes_one_key() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
-        h.insert("b".to_string(), "2".to_string());
-        h.insert("c".to_string(), "3".to_string());
-
-        assert_eq!(h.remove("b".to_string()), Some("2".to_string()));
-        assert_eq!(h.len(), 2);
+    fn get_mut_updates_value_in_place() {
+        let mut h = HashMap::<String, Vec<i32>>::new();
+        h.insert("a".to_string(), vec![1]);
 
-        assert_eq!(h.get("a".to_string()), Some(&"1".to_string()));
-        assert!(h.get("b".to_string()).is_none());
-        assert_eq!(h.get("c".to_string()), Some(&"3".to_string()));
+        h.get_mut("a").unwrap().push(2);
+        assert_eq!(h.get("a"), Some(&vec![1, 2]));
+        assert!(h.get_mut("b").is_none());
+        assert_eq!(h.len(), 1);
     }
 
     #[test]
-    fn remove_same_key_twice_second_time_returns_none() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
+    fn get_key_value_returns_stored_key() {
+        let mut h = HashMap::<String, i32>::new();
+        h.insert("a".to_string(), 1);
 
-        assert_eq!(h.remove("a".to_string()), Some("1".to_string()));
-        assert!(h.remove("a".to_string()).is_none());
-        assert_eq!(h.len(), 0);
+        assert_eq!(h.get_key_value("a"), Some((&"a".to_string(), &1)));
+        assert_eq!(h.get_key_value("b"), None);
     }
 
     #[test]
-    fn remove_many_keys_len_matches_remaining() {
-        let mut h = HashMap::<String, String>::new();
-
-        for i in 0..50 {
-            h.insert(format!("k{i}"), format!("v{i}"));
-        }
-        assert_eq!(h.len(), 50);
+    fn remove_entry_returns_owned_key_and_value() {
+        let mut h = HashMap::<String, i32>::new();
+        h.insert("a".to_string(), 1);
 
-        for i in 0..20 {
-            assert_eq!(h.remove(format!("k{i}")), Some(format!("v{i}")));
-        }
-
-        assert_eq!(h.len(), 30);
-        for i in 0..20 {
-            assert!(h.get(format!("k{i}")).is_none());
-        }
-        for i in 20..50 {
-            assert_eq!(h.get(format!("k{i}")), Some(&format!("v{i}")));
-        }
+        assert_eq!(h.remove_entry("a"), Some(("a".to_string(), 1)));
+        assert_eq!(h.remove_entry("a"), None);
+        assert!(h.is_empty());
     }
 
     #[test]
-    fn contains_returns_false_for_empty_hash_map() {
-        let h = HashMap::<String, String>::new();
-        assert!(!h.contains("a".to_string()));
+    fn try_insert_inserts_when_key_is_missing() {
+        let mut h = HashMap::<String, i32>::new();
+        *h.try_insert("a".to_string(), 1).unwrap() += 10;
+        assert_eq!(h.get("a"), Some(&11));
     }
 
     #[test]
-    fn contains_returns_true_after_insert() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
-        assert!(h.contains("a".to_string()));
+    fn try_insert_fails_with_existing_value() {
+        let mut h = HashMap::<String, i32>::new();
+        h.insert("a".to_string(), 1);
+
+        let err = h.try_insert("a".to_string(), 2).unwrap_err();
+        assert_eq!(err.entry.get(), &1);
+        assert_eq!(err.value, 2);
+        assert_eq!(
+            err.to_string(),
+            "failed to insert 2, key \"a\" already exists with value 1"
+        );
+
+        assert_eq!(h.get("a"), Some(&1));
+        assert_eq!(h.len(), 1);
     }
 
     #[test]
-    fn contains_returns_false_for_missing_key() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
-        assert!(!h.contains("b".to_string()));
+    fn get_many_mut_returns_references_in_request_order() {
+        let mut h = HashMap::<String, i32>::new();
+        h.insert("a".to_string(), 1);
+        h.insert("b".to_string(), 2);
+        h.insert("c".to_string(), 3);
+
+        let [c, a] = h.get_many_mut(["c", "a"]).unwrap();
+        std::mem::swap(c, a);
+
+        assert_eq!(h.get("a"), Some(&3));
+        assert_eq!(h.get("c"), Some(&1));
+        assert_eq!(h.get("b"), Some(&2));
     }
 
     #[test]
-    fn contains_still_true_after_value_update_same_key() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
-        h.insert("a".to_string(), "2".to_string());
-        assert!(h.contains("a".to_string()));
-        assert_eq!(h.len(), 1);
+    fn get_many_mut_returns_none_for_missing_key() {
+        let mut h = HashMap::<String, i32>::new();
+        h.insert("a".to_string(), 1);
+        assert!(h.get_many_mut(["a", "b"]).is_none());
     }
 
     #[test]
-    fn clear_makes_hash_map_empty() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
-        h.insert("b".to_string(), "2".to_string());
-        h.insert("c".to_string(), "3".to_string());
+    fn get_many_mut_returns_none_for_repeated_key() {
+        let mut h = HashMap::<String, i32>::new();
+        h.insert("a".to_string(), 1);
+        h.insert("b".to_string(), 2);
+        assert!(h.get_many_mut(["a", "b", "a"]).is_none());
+    }
 
-        h.clear();
+    #[test]
+    fn get_many_mut_handles_keys_in_the_same_bucket() {
+        let mut h = colliding_map(20);
+        let [x, y, z] = h
+            .get_many_mut([&(3 << 32), &(15 << 32), &(7 << 32)])
+            .unwrap();
+        *x += 100;
+        *y += 200;
+        *z += 300;
 
-        assert_eq!(h.len(), 0);
-        assert!(h.is_empty());
-        assert!(!h.contains("a".to_string()));
-        assert!(!h.contains("b".to_string()));
-        assert!(!h.contains("c".to_string()));
+        assert_eq!(h.get(&(3 << 32)), Some(&103));
+        assert_eq!(h.get(&(15 << 32)), Some(&215));
+        assert_eq!(h.get(&(7 << 32)), Some(&307));
     }
 
     #[test]
-    fn clear_on_empty_hash_map_keeps_it_empty() {
-        let mut h = HashMap::<String, String>::new();
-        h.clear();
-        assert_eq!(h.len(), 0);
-        assert!(h.is_empty());
+    fn get_many_mut_with_zero_keys_succeeds() {
+        let mut h = HashMap::<String, i32>::new();
+        let keys: [&str; 0] = [];
+        assert!(h.get_many_mut(keys).is_some());
     }
 
     #[test]
-    fn can_insert_after_clear() {
-        let mut h = HashMap::<String, String>::new();
-        h.insert("a".to_string(), "1".to_string());
-        h.clear();
+    fn accessors_find_keys_during_incremental_resize() {
+        let mut h = HashMap::<u64, u64>::with_seed(4);
+        h.set_resize_mode(ResizeMode::Incremental { buckets_per_op: 1 });
+        let mut next = 0;
+        while !h.is_resizing() {
+            h.insert(next, next);
+            next += 1;
+        }
 
-        h.insert("x".to_string(), "9".to_string());
+        assert_eq!(h.get_key_value(&0), Some((&0, &0)));
+        *h.get_mut(&1).unwrap() = 100;
+        assert_eq!(h.get(&1), Some(&100));
 
-        assert_eq!(h.len(), 1);
-        assert!(h.contains("x".to_string()));
-        assert_eq!(h.get("x".to_string()), Some(&"9".to_string()));
+        let [a, b] = h.get_many_mut([&2, &3]).unwrap();
+        *a = 200;
+        *b = 300;
+        assert_eq!(h.get(&2), Some(&200));
+        assert_eq!(h.get(&3), Some(&300));
+
+        assert_eq!(h.remove_entry(&4), Some((4, 4)));
+        assert_eq!(h.len() as u64, next - 1);
     }
 }
@@ -0,0 +1,590 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+
+use crate::{DefaultHashBuilder, Entry, HashMap, RandomState};
+
+const NIL: usize = usize::MAX;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum LinkOrder {
+    #[default]
+    Insertion,
+    Access,
+}
+
+#[derive(Clone)]
+struct Node<K, V> {
+    key: K,
+    value: V,
+    prev: usize,
+    next: usize,
+}
+
+#[derive(Clone)]
+pub struct LinkedHashMap<K, V, S = DefaultHashBuilder> {
+    nodes: Vec<Option<Node<K, V>>>,
+    free: Vec<usize>,
+    index: HashMap<K, usize, S>,
+    head: usize,
+    tail: usize,
+    order: LinkOrder,
+}
+
+pub struct Iter<'a, K, V> {
+    nodes: &'a [Option<Node<K, V>>],
+    front: usize,
+    back: usize,
+    remaining: usize,
+}
+
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> Default for LinkedHashMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K: Eq + Hash, V> LinkedHashMap<K, V> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_hasher(RandomState::with_seed(seed))
+    }
+
+    pub fn with_link_order(order: LinkOrder) -> Self {
+        let mut map = Self::new();
+        map.set_link_order(order);
+        map
+    }
+}
+
+impl<K, V, S> LinkedHashMap<K, V, S> {
+    pub fn len(&self) -> usize {
+        self.nodes.len() - self.free.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn link_order(&self) -> LinkOrder {
+        self.order
+    }
+
+    pub fn set_link_order(&mut self, order: LinkOrder) {
+        self.order = order;
+    }
+
+    pub fn front(&self) -> Option<(&K, &V)> {
+        self.pair(self.head)
+    }
+
+    pub fn back(&self) -> Option<(&K, &V)> {
+        self.pair(self.tail)
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            nodes: &self.nodes,
+            front: self.head,
+            back: self.tail,
+            remaining: self.len(),
+        }
+    }
+
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    fn node(&self, id: usize) -> &Node<K, V> {
+        self.nodes[id]
+            .as_ref()
+            .expect("linked node must be occupied")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Node<K, V> {
+        self.nodes[id]
+            .as_mut()
+            .expect("linked node must be occupied")
+    }
+
+    fn pair(&self, id: usize) -> Option<(&K, &V)> {
+        let node = self.nodes.get(id)?.as_ref()?;
+        Some((&node.key, &node.value))
+    }
+
+    fn unlink(&mut self, id: usize) {
+        let (prev, next) = {
+            let node = self.node(id);
+            (node.prev, node.next)
+        };
+
+        match prev {
+            NIL => self.head = next,
+            p => self.node_mut(p).next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            n => self.node_mut(n).prev = prev,
+        }
+    }
+
+    fn link_back(&mut self, id: usize) {
+        let tail = self.tail;
+        let node = self.node_mut(id);
+        node.prev = tail;
+        node.next = NIL;
+
+        match tail {
+            NIL => self.head = id,
+            t => self.node_mut(t).next = id,
+        }
+        self.tail = id;
+    }
+
+    fn link_front(&mut self, id: usize) {
+        let head = self.head;
+        let node = self.node_mut(id);
+        node.prev = NIL;
+        node.next = head;
+
+        match head {
+            NIL => self.tail = id,
+            h => self.node_mut(h).prev = id,
+        }
+        self.head = id;
+    }
+
+    fn promote(&mut self, id: usize) {
+        if self.order == LinkOrder::Access && self.tail != id {
+            self.unlink(id);
+            self.link_back(id);
+        }
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> LinkedHashMap<K, V, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            nodes: Vec::with_capacity(capacity),
+            free: Vec::new(),
+            index: HashMap::with_capacity_and_hasher(capacity, hash_builder),
+            head: NIL,
+            tail: NIL,
+            order: LinkOrder::Insertion,
+        }
+    }
+
+    pub fn hasher(&self) -> &S {
+        self.index.hasher()
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.index.contains(key)
+    }
+
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let id = *self.index.get(key)?;
+        Some(&self.node(id).value)
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let id = *self.index.get(key)?;
+        self.promote(id);
+        Some(&self.node(id).value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let id = *self.index.get(key)?;
+        self.promote(id);
+        Some(&mut self.node_mut(id).value)
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.remove_entry(key).map(|(_, v)| v)
+    }
+
+    pub fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let id = *self.index.get(key)?;
+        self.remove_node(id)
+    }
+
+    pub fn pop_front(&mut self) -> Option<(K, V)> {
+        self.remove_node(self.head)
+    }
+
+    pub fn pop_back(&mut self) -> Option<(K, V)> {
+        self.remove_node(self.tail)
+    }
+
+    pub fn move_to_back<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let Some(&id) = self.index.get(key) else {
+            return false;
+        };
+        self.unlink(id);
+        self.link_back(id);
+        true
+    }
+
+    pub fn move_to_front<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let Some(&id) = self.index.get(key) else {
+            return false;
+        };
+        self.unlink(id);
+        self.link_front(id);
+        true
+    }
+
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.free.clear();
+        self.index.clear();
+        self.head = NIL;
+        self.tail = NIL;
+    }
+
+    fn remove_node(&mut self, id: usize) -> Option<(K, V)> {
+        self.nodes.get(id)?.as_ref()?;
+        self.unlink(id);
+
+        let node = self.nodes[id].take().expect("linked node must be occupied");
+        self.index.remove(&node.key);
+        self.free.push(id);
+        Some((node.key, node.value))
+    }
+}
+
+impl<K: Eq + Hash + Clone, V, S: BuildHasher> LinkedHashMap<K, V, S> {
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let id = match self.index.entry(key) {
+            Entry::Occupied(entry) => {
+                let id = *entry.get();
+                self.promote(id);
+                return Some(std::mem::replace(&mut self.node_mut(id).value, value));
+            }
+            Entry::Vacant(entry) => {
+                let node = Node {
+                    key: entry.key().clone(),
+                    value,
+                    prev: NIL,
+                    next: NIL,
+                };
+                let id = match self.free.pop() {
+                    Some(id) => {
+                        self.nodes[id] = Some(node);
+                        id
+                    }
+                    None => {
+                        self.nodes.push(Some(node));
+                        self.nodes.len() - 1
+                    }
+                };
+                entry.insert(id);
+                id
+            }
+        };
+
+        self.link_back(id);
+        None
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for LinkedHashMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, S> FromIterator<(K, V)> for LinkedHashMap<K, V, S>
+where
+    K: Eq + Hash + Clone,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::with_hasher(S::default());
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Eq + Hash + Clone, V, S: BuildHasher> Extend<(K, V)> for LinkedHashMap<K, V, S> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a LinkedHashMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let node = self.nodes[self.front].as_ref()?;
+        self.front = node.next;
+        self.remaining -= 1;
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let node = self.nodes[self.back].as_ref()?;
+        self.back = node.prev;
+        self.remaining -= 1;
+        Some((&node.key, &node.value))
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(k, _)| k)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Keys<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(k, _)| k)
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, v)| v)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> DoubleEndedIterator for Values<'_, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, v)| v)
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+impl<K, V> ExactSizeIterator for Keys<'_, K, V> {}
+impl<K, V> ExactSizeIterator for Values<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+impl<K, V> FusedIterator for Keys<'_, K, V> {}
+impl<K, V> FusedIterator for Values<'_, K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    map_test_suite!(LinkedHashMap);
+
+    fn letters(n: usize, order: LinkOrder) -> LinkedHashMap<char, usize> {
+        let mut map = LinkedHashMap::with_link_order(order);
+        map.extend(('a'..).take(n).enumerate().map(|(i, c)| (c, i)));
+        map
+    }
+
+    fn keys(map: &LinkedHashMap<char, usize>) -> String {
+        map.keys().collect()
+    }
+
+    #[test]
+    fn iteration_follows_insertion_order() {
+        let h = letters(5, LinkOrder::Insertion);
+        assert_eq!(keys(&h), "abcde");
+        assert_eq!(h.keys().rev().collect::<String>(), "edcba");
+        assert_eq!(h.iter().len(), 5);
+    }
+
+    #[test]
+    fn reinsert_keeps_position_in_insertion_order() {
+        let mut h = letters(3, LinkOrder::Insertion);
+        assert_eq!(h.insert('a', 10), Some(0));
+        assert_eq!(keys(&h), "abc");
+        assert_eq!(h.peek(&'a'), Some(&10));
+    }
+
+    #[test]
+    fn get_does_not_reorder_in_insertion_order() {
+        let mut h = letters(3, LinkOrder::Insertion);
+        assert_eq!(h.get(&'a'), Some(&0));
+        assert_eq!(h.get_mut(&'b'), Some(&mut 1));
+        assert_eq!(keys(&h), "abc");
+    }
+
+    #[test]
+    fn access_order_promotes_on_get_insert_and_get_mut() {
+        let mut h = letters(4, LinkOrder::Access);
+
+        assert_eq!(h.get(&'a'), Some(&0));
+        assert_eq!(keys(&h), "bcda");
+
+        h.insert('b', 10);
+        assert_eq!(keys(&h), "cdab");
+
+        *h.get_mut(&'c').unwrap() += 1;
+        assert_eq!(keys(&h), "dabc");
+        assert_eq!(h.front(), Some((&'d', &3)));
+        assert_eq!(h.back(), Some((&'c', &3)));
+    }
+
+    #[test]
+    fn peek_and_contains_never_promote() {
+        let mut h = letters(3, LinkOrder::Access);
+        assert_eq!(h.peek(&'a'), Some(&0));
+        assert!(h.contains(&'a'));
+        assert_eq!(keys(&h), "abc");
+
+        assert_eq!(h.get(&'z'), None);
+        assert_eq!(keys(&h), "abc");
+    }
+
+    #[test]
+    fn move_to_back_and_front() {
+        let mut h = letters(4, LinkOrder::Insertion);
+        assert!(h.move_to_back(&'a'));
+        assert_eq!(keys(&h), "bcda");
+
+        assert!(h.move_to_front(&'d'));
+        assert_eq!(keys(&h), "dbca");
+
+        assert!(h.move_to_front(&'d'));
+        assert!(h.move_to_back(&'a'));
+        assert_eq!(keys(&h), "dbca");
+
+        assert!(!h.move_to_back(&'z'));
+    }
+
+    #[test]
+    fn front_back_and_pop() {
+        let mut h = letters(3, LinkOrder::Insertion);
+        assert_eq!(h.front(), Some((&'a', &0)));
+        assert_eq!(h.back(), Some((&'c', &2)));
+
+        assert_eq!(h.pop_front(), Some(('a', 0)));
+        assert_eq!(h.pop_back(), Some(('c', 2)));
+        assert_eq!(h.pop_back(), Some(('b', 1)));
+        assert_eq!(h.pop_front(), None);
+        assert_eq!(h.front(), None);
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn remove_from_middle_relinks_neighbours() {
+        let mut h = letters(5, LinkOrder::Insertion);
+        assert_eq!(h.remove(&'c'), Some(2));
+        assert_eq!(keys(&h), "abde");
+        assert_eq!(h.keys().rev().collect::<String>(), "edba");
+    }
+
+    #[test]
+    fn freed_slots_are_reused() {
+        let mut h = LinkedHashMap::new();
+        for round in 0..10 {
+            for i in 0..100 {
+                h.insert(i, round);
+            }
+            while h.pop_front().is_some() {}
+        }
+
+        assert!(h.nodes.len() <= 100);
+        assert!(h.is_empty());
+    }
+
+    #[test]
+    fn recency_tracking_evicts_least_recently_used() {
+        let mut recent = LinkedHashMap::with_link_order(LinkOrder::Access);
+        for id in 0..1000 {
+            recent.insert(id, ());
+            if id % 3 == 0 {
+                recent.get(&0);
+            }
+            if recent.len() > 10 {
+                recent.pop_front();
+            }
+        }
+
+        assert_eq!(recent.len(), 10);
+        assert_eq!(recent.back().map(|(k, _)| *k), Some(0));
+        assert_eq!(recent.front().map(|(k, _)| *k), Some(991));
+    }
+}
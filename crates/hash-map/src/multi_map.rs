@@ -0,0 +1,350 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::iter::FusedIterator;
+use std::slice;
+
+use crate::{DefaultHashBuilder, HashMap, RandomState};
+
+pub struct MultiMap<K, V, S = DefaultHashBuilder> {
+    map: HashMap<K, Vec<V>, S>,
+    values: usize,
+}
+
+pub struct Iter<'a, K, V> {
+    lists: crate::Iter<'a, K, Vec<V>>,
+    current: Option<(&'a K, slice::Iter<'a, V>)>,
+    remaining: usize,
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> Default for MultiMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K: Eq + Hash, V> MultiMap<K, V> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_hasher(RandomState::with_seed(seed))
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> MultiMap<K, V, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            map: HashMap::with_hasher(hash_builder),
+            values: 0,
+        }
+    }
+
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+
+    pub fn len_keys(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn len_values(&self) -> usize {
+        self.values
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.map.entry(key).or_default().push(value);
+        self.values += 1;
+    }
+
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.contains(key)
+    }
+
+    pub fn contains<Q>(&self, key: &Q, value: &V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+        V: PartialEq,
+    {
+        self.get_all(key)
+            .is_some_and(|values| values.contains(value))
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get_all(key)?.first()
+    }
+
+    pub fn get_all<Q>(&self, key: &Q) -> Option<&[V]>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.get(key).map(Vec::as_slice)
+    }
+
+    pub fn remove_value<Q>(&mut self, key: &Q, value: &V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+        V: PartialEq,
+    {
+        let Some(values) = self.map.get_mut(key) else {
+            return false;
+        };
+        let Some(pos) = values.iter().position(|v| v == value) else {
+            return false;
+        };
+
+        values.remove(pos);
+        if values.is_empty() {
+            self.map.remove(key);
+        }
+        self.values -= 1;
+        true
+    }
+
+    pub fn remove_all<Q>(&mut self, key: &Q) -> Option<Vec<V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let values = self.map.remove(key)?;
+        self.values -= values.len();
+        Some(values)
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&K, &V) -> bool,
+    {
+        let mut removed = 0;
+        self.map.retain(|k, values| {
+            let before = values.len();
+            values.retain(|v| f(k, v));
+            removed += before - values.len();
+            !values.is_empty()
+        });
+        self.values -= removed;
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.values = 0;
+    }
+}
+
+impl<K, V, S> MultiMap<K, V, S> {
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            lists: self.map.iter(),
+            current: None,
+            remaining: self.values,
+        }
+    }
+
+    pub fn keys(&self) -> crate::Keys<'_, K, Vec<V>> {
+        self.map.keys()
+    }
+}
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for MultiMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.map.iter()).finish()
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> FromIterator<(K, V)> for MultiMap<K, V, S> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::with_hasher(S::default());
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Extend<(K, V)> for MultiMap<K, V, S> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a MultiMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((k, values)) = &mut self.current
+                && let Some(v) = values.next()
+            {
+                self.remaining -= 1;
+                return Some((*k, v));
+            }
+
+            let (k, values) = self.lists.next()?;
+            self.current = Some((k, values.iter()));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::MultiMap;
+
+    fn index() -> MultiMap<&'static str, u32> {
+        [("rust", 1), ("go", 2), ("rust", 3), ("rust", 4), ("go", 5)]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn new_map_is_empty() {
+        let m = MultiMap::<String, i32>::new();
+        assert!(m.is_empty());
+        assert_eq!(m.len_keys(), 0);
+        assert_eq!(m.len_values(), 0);
+        assert_eq!(m.iter().next(), None);
+    }
+
+    #[test]
+    fn insert_appends_values_in_order() {
+        let m = index();
+        assert_eq!(m.len_keys(), 2);
+        assert_eq!(m.len_values(), 5);
+        assert_eq!(m.get_all("rust"), Some(&[1, 3, 4][..]));
+        assert_eq!(m.get("go"), Some(&2));
+        assert_eq!(m.get_all("c"), None);
+    }
+
+    #[test]
+    fn duplicate_values_are_kept() {
+        let mut m = MultiMap::new();
+        m.insert("a", 1);
+        m.insert("a", 1);
+        assert_eq!(m.get_all("a"), Some(&[1, 1][..]));
+        assert_eq!(m.len_values(), 2);
+    }
+
+    #[test]
+    fn contains_checks_key_and_pair() {
+        let m = index();
+        assert!(m.contains_key("go"));
+        assert!(m.contains("go", &5));
+        assert!(!m.contains("go", &1));
+        assert!(!m.contains("c", &1));
+    }
+
+    #[test]
+    fn remove_value_removes_one_occurrence() {
+        let mut m = index();
+        assert!(m.remove_value("rust", &3));
+        assert!(!m.remove_value("rust", &3));
+        assert!(!m.remove_value("c", &1));
+
+        assert_eq!(m.get_all("rust"), Some(&[1, 4][..]));
+        assert_eq!(m.len_values(), 4);
+    }
+
+    #[test]
+    fn removing_last_value_removes_key() {
+        let mut m = index();
+        assert!(m.remove_value("go", &2));
+        assert!(m.remove_value("go", &5));
+
+        assert!(!m.contains_key("go"));
+        assert_eq!(m.get_all("go"), None);
+        assert_eq!(m.len_keys(), 1);
+    }
+
+    #[test]
+    fn remove_all_returns_every_value() {
+        let mut m = index();
+        assert_eq!(m.remove_all("rust"), Some(vec![1, 3, 4]));
+        assert_eq!(m.remove_all("rust"), None);
+        assert_eq!(m.len_keys(), 1);
+        assert_eq!(m.len_values(), 2);
+    }
+
+    #[test]
+    fn retain_filters_pairs_and_drops_empty_keys() {
+        let mut m = index();
+        m.retain(|k, v| *k == "rust" || *v > 100);
+
+        assert!(!m.contains_key("go"));
+        assert_eq!(m.len_keys(), 1);
+        assert_eq!(m.len_values(), 3);
+
+        m.retain(|_, v| v % 2 == 0);
+        assert_eq!(m.get_all("rust"), Some(&[4][..]));
+        assert_eq!(m.len_values(), 1);
+    }
+
+    #[test]
+    fn iter_flattens_all_pairs() {
+        let m = index();
+        let it = m.iter();
+        assert_eq!(it.len(), 5);
+
+        let mut pairs: Vec<_> = it.map(|(k, v)| (*k, *v)).collect();
+        pairs.sort();
+        assert_eq!(
+            pairs,
+            [("go", 2), ("go", 5), ("rust", 1), ("rust", 3), ("rust", 4)]
+        );
+    }
+
+    #[test]
+    fn keys_visits_each_key_once() {
+        let m = index();
+        let mut keys: Vec<_> = m.keys().copied().collect();
+        keys.sort();
+        assert_eq!(keys, ["go", "rust"]);
+    }
+
+    #[test]
+    fn clear_resets_counts() {
+        let mut m = index();
+        m.clear();
+        assert!(m.is_empty());
+        assert_eq!(m.len_values(), 0);
+    }
+
+    #[test]
+    fn debug_shows_value_lists() {
+        let mut m = MultiMap::new();
+        m.insert("a", 1);
+        m.insert("a", 2);
+        assert_eq!(format!("{m:?}"), r#"{"a": [1, 2]}"#);
+    }
+}
@@ -0,0 +1,145 @@
+use std::fs::File;
+use std::hash::{BuildHasher, Hasher};
+use std::io::Read;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::hashers::SipHasher13;
+
+static PROCESS_KEYS: OnceLock<(u64, u64)> = OnceLock::new();
+static INSTANCE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RandomState {
+    k0: u64,
+    k1: u64,
+}
+
+impl Default for RandomState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RandomState {
+    pub fn new() -> Self {
+        let (base0, base1) = *PROCESS_KEYS.get_or_init(process_entropy);
+        let instance = INSTANCE_COUNTER.fetch_add(1, Ordering::Relaxed);
+
+        Self {
+            k0: mix(base0, base1, instance.wrapping_mul(2)),
+            k1: mix(base0, base1, instance.wrapping_mul(2).wrapping_add(1)),
+        }
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        let k0 = splitmix64(seed);
+        Self {
+            k0,
+            k1: splitmix64(k0),
+        }
+    }
+
+    pub fn keys(&self) -> (u64, u64) {
+        (self.k0, self.k1)
+    }
+}
+
+impl BuildHasher for RandomState {
+    type Hasher = SipHasher13;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        SipHasher13::new_with_keys(self.k0, self.k1)
+    }
+}
+
+fn process_entropy() -> (u64, u64) {
+    os_entropy().unwrap_or_else(address_space_entropy)
+}
+
+fn os_entropy() -> Option<(u64, u64)> {
+    let mut bytes = [0u8; 16];
+    File::open("/dev/urandom")
+        .and_then(|mut f| f.read_exact(&mut bytes))
+        .ok()?;
+
+    let (a, b) = bytes.split_at(8);
+    Some((
+        u64::from_le_bytes(a.try_into().ok()?),
+        u64::from_le_bytes(b.try_into().ok()?),
+    ))
+}
+
+fn address_space_entropy() -> (u64, u64) {
+    let stack = 0u8;
+    let heap = Box::new(0u8);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+
+    let mut h = SipHasher13::new_with_keys(nanos, std::process::id() as u64);
+    h.write_usize(&stack as *const u8 as usize);
+    h.write_usize(&*heap as *const u8 as usize);
+    h.write_usize(address_space_entropy as *const () as usize);
+    let k0 = h.finish();
+    h.write_u64(k0);
+
+    (k0, h.finish())
+}
+
+fn mix(k0: u64, k1: u64, n: u64) -> u64 {
+    let mut h = SipHasher13::new_with_keys(k0, k1);
+    h.write_u64(n);
+    h.finish()
+}
+
+pub(crate) fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_states_have_different_keys() {
+        let a = RandomState::new();
+        let b = RandomState::new();
+        assert_ne!(a.keys(), b.keys());
+        assert_ne!(a.hash_one("key"), b.hash_one("key"));
+    }
+
+    #[test]
+    fn same_state_hashes_consistently() {
+        let s = RandomState::new();
+        assert_eq!(s.hash_one(42u64), s.hash_one(42u64));
+        assert_eq!(s.clone().hash_one(42u64), s.hash_one(42u64));
+    }
+
+    #[test]
+    fn with_seed_is_reproducible() {
+        assert_eq!(RandomState::with_seed(7), RandomState::with_seed(7));
+        assert_eq!(
+            RandomState::with_seed(7).hash_one("key"),
+            RandomState::with_seed(7).hash_one("key")
+        );
+    }
+
+    #[test]
+    fn different_seeds_give_different_keys() {
+        assert_ne!(RandomState::with_seed(1), RandomState::with_seed(2));
+        let (k0, k1) = RandomState::with_seed(0).keys();
+        assert_ne!(k0, k1);
+    }
+
+    #[test]
+    fn fallback_entropy_produces_distinct_keys() {
+        let (k0, k1) = address_space_entropy();
+        assert_ne!(k0, k1);
+    }
+}
@@ -0,0 +1,336 @@
+use crate::HashMap;
+use crate::bucket::Bucket;
+use crate::capacity::pairs_for;
+
+pub const DEFAULT_BUCKETS_PER_OP: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ResizeMode {
+    #[default]
+    Immediate,
+    Incremental {
+        buckets_per_op: usize,
+    },
+}
+
+impl ResizeMode {
+    pub fn incremental() -> Self {
+        ResizeMode::Incremental {
+            buckets_per_op: DEFAULT_BUCKETS_PER_OP,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct Migration<K, V> {
+    pub(crate) buckets: Vec<Bucket<K, V>>,
+    pub(crate) capacity: usize,
+    pub(crate) next: usize,
+}
+
+impl<K, V, S> HashMap<K, V, S> {
+    pub fn resize_mode(&self) -> ResizeMode {
+        self.resize_mode
+    }
+
+    pub fn set_resize_mode(&mut self, mode: ResizeMode) {
+        if let ResizeMode::Incremental { buckets_per_op } = mode {
+            assert!(buckets_per_op > 0, "buckets_per_op must be positive");
+        }
+        self.resize_mode = mode;
+        if mode == ResizeMode::Immediate {
+            self.finish_resize();
+        }
+    }
+
+    pub fn is_resizing(&self) -> bool {
+        self.migration.is_some()
+    }
+
+    pub(crate) fn start_resize(&mut self, new_capacity: usize) {
+        debug_assert!(
+            self.migration.is_none(),
+            "a migration must finish before the next growth threshold"
+        );
+        self.finish_resize();
+
+        let old_buckets = std::mem::replace(
+            &mut self.buckets,
+            (0..new_capacity).map(|_| Bucket::new()).collect(),
+        );
+        let old_capacity = std::mem::replace(&mut self.capacity, new_capacity);
+
+        self.migration = Some(Migration {
+            buckets: old_buckets,
+            capacity: old_capacity,
+            next: 0,
+        });
+
+        if self.resize_mode == ResizeMode::Immediate {
+            self.finish_resize();
+        }
+    }
+
+    pub(crate) fn migrate_step(&mut self) {
+        let ResizeMode::Incremental { buckets_per_op } = self.resize_mode else {
+            return;
+        };
+        let Some(migration) = self.migration.as_ref() else {
+            return;
+        };
+
+        let remaining = migration.buckets.len() - migration.next;
+        let headroom = pairs_for(self.capacity, self.max_load_factor)
+            .saturating_sub(self.counter)
+            .max(1);
+        self.migrate(buckets_per_op.max(remaining.div_ceil(headroom)));
+    }
+
+    pub(crate) fn finish_resize(&mut self) {
+        self.migrate(usize::MAX);
+    }
+
+    fn migrate(&mut self, max_buckets: usize) {
+        let Some(migration) = self.migration.as_mut() else {
+            return;
+        };
+
+        let mut moved = 0;
+        while moved < max_buckets && migration.next < migration.buckets.len() {
+            let bucket = std::mem::replace(&mut migration.buckets[migration.next], Bucket::new());
+            for (hash, (k, v)) in bucket.into_parts() {
+                let index = (hash as usize) % self.capacity;
+                self.buckets[index].insert(hash, k, v, self.key_tag);
+            }
+            migration.next += 1;
+            moved += 1;
+        }
+
+        if migration.next == migration.buckets.len() {
+            self.migration = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::Lcg;
+
+    fn incremental_map() -> HashMap<u64, u64> {
+        let mut h = HashMap::with_seed(21);
+        h.set_resize_mode(ResizeMode::Incremental { buckets_per_op: 2 });
+        h
+    }
+
+    fn fill_until_resizing(h: &mut HashMap<u64, u64>) -> u64 {
+        let mut next = 0;
+        while !h.is_resizing() {
+            h.insert(next, next);
+            next += 1;
+        }
+        next
+    }
+
+    #[test]
+    fn default_mode_is_immediate() {
+        let mut h = HashMap::<u64, u64>::new();
+        assert_eq!(h.resize_mode(), ResizeMode::Immediate);
+        for i in 0..1000 {
+            h.insert(i, i);
+            assert!(!h.is_resizing());
+        }
+    }
+
+    #[test]
+    fn incremental_resize_keeps_both_tables_until_migrated() {
+        let mut h = incremental_map();
+        let inserted = fill_until_resizing(&mut h);
+
+        let migration = h.migration.as_ref().unwrap();
+        assert_eq!(migration.capacity * 2, h.capacity);
+        assert!(migration.next <= 2);
+
+        for k in 0..inserted {
+            assert_eq!(h.get(&k), Some(&k));
+        }
+    }
+
+    #[test]
+    fn each_write_migrates_a_bounded_number_of_buckets() {
+        let mut h = incremental_map();
+        let mut next = fill_until_resizing(&mut h);
+
+        while h.is_resizing() {
+            let before = h.migration.as_ref().unwrap().next;
+            h.insert(next, next);
+            next += 1;
+            if let Some(migration) = h.migration.as_ref() {
+                assert!(migration.next - before <= 2);
+            }
+        }
+
+        assert_eq!(h.len() as u64, next);
+        for k in 0..next {
+            assert_eq!(h.get(&k), Some(&k));
+        }
+    }
+
+    #[test]
+    fn migration_finishes_before_the_next_growth_threshold() {
+        let mut h = HashMap::<u64, u64>::with_seed(8);
+        h.set_resize_mode(ResizeMode::Incremental { buckets_per_op: 1 });
+        let mut resizes = 0;
+        let mut next = 0;
+
+        while resizes < 3 {
+            let capacity = h.capacity;
+            let before = h.migration.as_ref().map(|m| m.next);
+
+            h.insert(next, next);
+            next += 1;
+
+            if h.capacity != capacity {
+                assert_eq!(before, None, "previous migration was still running");
+                resizes += 1;
+            } else if let (Some(before), Some(after)) = (before, h.migration.as_ref()) {
+                assert!(after.next - before <= 2, "moved {}", after.next - before);
+            }
+        }
+
+        for k in 0..next {
+            assert_eq!(h.get(&k), Some(&k));
+        }
+    }
+
+    #[test]
+    fn removals_during_migration_keep_the_quota_bounded() {
+        let mut h = incremental_map();
+        let mut next = fill_until_resizing(&mut h);
+        let mut rng = Lcg::new(3);
+
+        while h.is_resizing() {
+            let before = h.migration.as_ref().unwrap().next;
+            if rng.below(3) == 0 {
+                h.remove(&rng.below(next));
+            } else {
+                h.insert(next, next);
+                next += 1;
+            }
+            if let Some(migration) = h.migration.as_ref() {
+                assert!(migration.next - before <= 2);
+            }
+        }
+    }
+
+    #[test]
+    fn switching_to_immediate_mode_finishes_migration() {
+        let mut h = incremental_map();
+        let inserted = fill_until_resizing(&mut h);
+
+        h.set_resize_mode(ResizeMode::Immediate);
+        assert!(!h.is_resizing());
+        for k in 0..inserted {
+            assert_eq!(h.get(&k), Some(&k));
+        }
+    }
+
+    #[test]
+    fn insert_of_key_still_in_old_table_replaces_it() {
+        let mut h = incremental_map();
+        let inserted = fill_until_resizing(&mut h);
+        let len = h.len();
+
+        for k in 0..inserted {
+            h.insert(k, k + 1000);
+        }
+
+        assert_eq!(h.len(), len);
+        for k in 0..inserted {
+            assert_eq!(h.get(&k), Some(&(k + 1000)));
+        }
+    }
+
+    #[test]
+    fn remove_finds_keys_in_either_table() {
+        let mut h = incremental_map();
+        let inserted = fill_until_resizing(&mut h);
+
+        for k in 0..inserted {
+            assert_eq!(h.remove(&k), Some(k));
+        }
+        assert!(h.is_empty());
+        assert_eq!(h.iter().count(), 0);
+    }
+
+    #[test]
+    fn entry_finds_keys_in_old_table() {
+        let mut h = incremental_map();
+        let inserted = fill_until_resizing(&mut h);
+
+        for k in 0..inserted {
+            *h.entry(k).or_insert(0) += 1;
+        }
+
+        assert_eq!(h.len() as u64, inserted);
+        for k in 0..inserted {
+            assert_eq!(h.get(&k), Some(&(k + 1)));
+        }
+    }
+
+    #[test]
+    fn iteration_during_migration_sees_every_pair_once() {
+        let mut h = incremental_map();
+        let inserted = fill_until_resizing(&mut h);
+
+        let mut keys: Vec<u64> = h.keys().copied().collect();
+        keys.sort();
+        assert_eq!(keys, (0..inserted).collect::<Vec<_>>());
+        assert_eq!(h.iter().len() as u64, inserted);
+    }
+
+    #[test]
+    fn bulk_operations_during_migration_keep_len_correct() {
+        let mut h = incremental_map();
+        let inserted = fill_until_resizing(&mut h);
+
+        h.retain(|k, _| k % 2 == 0);
+        assert_eq!(h.len() as u64, inserted.div_ceil(2));
+        assert!(!h.is_resizing());
+
+        let mut h = incremental_map();
+        let inserted = fill_until_resizing(&mut h);
+        assert_eq!(h.drain().count() as u64, inserted);
+        assert!(h.is_empty());
+
+        let mut h = incremental_map();
+        fill_until_resizing(&mut h);
+        h.clear();
+        assert!(h.is_empty());
+        assert!(!h.is_resizing());
+        assert!(h.get(&0).is_none());
+    }
+
+    #[test]
+    fn incremental_mode_matches_immediate_mode_on_random_workload() {
+        let mut incremental = incremental_map();
+        let mut immediate = HashMap::<u64, u64>::with_seed(21);
+        let mut rng = Lcg::new(5);
+
+        for _ in 0..30_000 {
+            let key = rng.below(5000);
+            if rng.below(3) == 0 {
+                assert_eq!(incremental.remove(&key), immediate.remove(&key));
+            } else {
+                let value = rng.next_u64();
+                incremental.insert(key, value);
+                immediate.insert(key, value);
+            }
+            assert_eq!(incremental.len(), immediate.len());
+        }
+
+        for key in 0..5000 {
+            assert_eq!(incremental.get(&key), immediate.get(&key));
+        }
+    }
+}
@@ -0,0 +1,338 @@
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+
+use crate::{DefaultHashBuilder, RandomState};
+
+const ROBIN_HOOD_INITIAL_CAPACITY: usize = 16;
+
+struct Slot<K, V> {
+    fragment: u32,
+    key: K,
+    value: V,
+}
+
+pub struct RobinHoodMap<K, V, S = DefaultHashBuilder> {
+    slots: Vec<Option<Slot<K, V>>>,
+    counter: usize,
+    hash_builder: S,
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> Default for RobinHoodMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K: Eq + Hash, V> RobinHoodMap<K, V> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_hasher(RandomState::with_seed(seed))
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> RobinHoodMap<K, V, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        let mut slots = ROBIN_HOOD_INITIAL_CAPACITY;
+        while capacity * 8 > slots * 7 {
+            slots *= 2;
+        }
+
+        Self {
+            slots: (0..slots).map(|_| None).collect(),
+            counter: 0,
+            hash_builder,
+        }
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    pub fn len(&self) -> usize {
+        self.counter
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counter == 0
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get(key).is_some()
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let pos = self.find(key)?;
+        self.slots[pos].as_ref().map(|slot| &slot.value)
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if self.should_resize() {
+            self.resize();
+        }
+
+        let fragment = self.fragment(&key);
+        if let Some(pos) = self.find_with_fragment(fragment, &key) {
+            return self.slots[pos]
+                .as_mut()
+                .map(|slot| std::mem::replace(&mut slot.value, value));
+        }
+
+        self.place(Slot {
+            fragment,
+            key,
+            value,
+        });
+        self.counter += 1;
+        None
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let mut pos = self.find(key)?;
+        let removed = self.slots[pos].take()?;
+        self.counter -= 1;
+
+        loop {
+            let next = (pos + 1) & self.mask();
+            match &self.slots[next] {
+                Some(slot) if self.distance(slot.fragment, next) > 0 => {
+                    self.slots[pos] = self.slots[next].take();
+                    pos = next;
+                }
+                _ => break,
+            }
+        }
+
+        Some(removed.value)
+    }
+
+    pub fn clear(&mut self) {
+        for slot in self.slots.iter_mut() {
+            *slot = None;
+        }
+        self.counter = 0;
+    }
+
+    fn fragment<Q: ?Sized + Hash>(&self, key: &Q) -> u32 {
+        self.hash_builder.hash_one(key) as u32
+    }
+
+    fn mask(&self) -> usize {
+        self.slots.len() - 1
+    }
+
+    fn ideal(&self, fragment: u32) -> usize {
+        fragment as usize & self.mask()
+    }
+
+    fn distance(&self, fragment: u32, pos: usize) -> usize {
+        pos.wrapping_sub(self.ideal(fragment)) & self.mask()
+    }
+
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.find_with_fragment(self.fragment(key), key)
+    }
+
+    fn find_with_fragment<Q>(&self, fragment: u32, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq,
+    {
+        let mut pos = self.ideal(fragment);
+        let mut dist = 0;
+
+        loop {
+            let slot = self.slots[pos].as_ref()?;
+            if self.distance(slot.fragment, pos) < dist {
+                return None;
+            }
+            if slot.fragment == fragment && slot.key.borrow() == key {
+                return Some(pos);
+            }
+
+            pos = (pos + 1) & self.mask();
+            dist += 1;
+        }
+    }
+
+    fn place(&mut self, mut incoming: Slot<K, V>) {
+        let mask = self.mask();
+        let mut pos = self.ideal(incoming.fragment);
+        let mut dist = 0;
+
+        loop {
+            match &mut self.slots[pos] {
+                None => {
+                    self.slots[pos] = Some(incoming);
+                    return;
+                }
+                Some(resident) => {
+                    let resident_dist = pos.wrapping_sub(resident.fragment as usize) & mask;
+                    if resident_dist < dist {
+                        std::mem::swap(resident, &mut incoming);
+                        dist = resident_dist;
+                    }
+                }
+            }
+
+            pos = (pos + 1) & mask;
+            dist += 1;
+        }
+    }
+
+    fn should_resize(&self) -> bool {
+        (self.counter + 1) * 8 > self.slots.len() * 7
+    }
+
+    fn resize(&mut self) {
+        let new_capacity = self.slots.len() * 2;
+        assert!(
+            new_capacity <= u32::MAX as usize + 1,
+            "RobinHoodMap capacity overflow"
+        );
+
+        let old = std::mem::replace(&mut self.slots, (0..new_capacity).map(|_| None).collect());
+        for slot in old.into_iter().flatten() {
+            self.place(slot);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{IdentityState, Lcg};
+
+    map_test_suite!(RobinHoodMap);
+
+    type IdentityMap = RobinHoodMap<u64, u64, IdentityState>;
+
+    fn assert_robin_hood_invariant<K, V, S>(m: &RobinHoodMap<K, V, S>)
+    where
+        K: Eq + Hash,
+        S: BuildHasher,
+    {
+        let len = m.slots.len();
+        for pos in 0..len {
+            let next = (pos + 1) % len;
+            if let (Some(a), Some(b)) = (&m.slots[pos], &m.slots[next]) {
+                assert!(m.distance(b.fragment, next) <= m.distance(a.fragment, pos) + 1);
+            }
+            if let (None, Some(b)) = (&m.slots[pos], &m.slots[next]) {
+                assert_eq!(m.distance(b.fragment, next), 0);
+            }
+        }
+        assert_eq!(m.slots.iter().flatten().count(), m.counter);
+    }
+
+    #[test]
+    fn capacity_is_power_of_two() {
+        let m = RobinHoodMap::<u64, u64>::with_capacity_and_hasher(100, RandomState::new());
+        assert!(m.slots.len().is_power_of_two());
+        assert!(m.slots.len() * 7 >= 100 * 8);
+    }
+
+    #[test]
+    fn colliding_keys_are_placed_in_consecutive_slots() {
+        let mut m = IdentityMap::default();
+        m.insert(1, 10);
+        m.insert(17, 170);
+        m.insert(33, 330);
+
+        assert_eq!(m.slots[1].as_ref().map(|s| s.key), Some(1));
+        assert_eq!(m.slots[2].as_ref().map(|s| s.key), Some(17));
+        assert_eq!(m.slots[3].as_ref().map(|s| s.key), Some(33));
+        assert_robin_hood_invariant(&m);
+    }
+
+    #[test]
+    fn rich_resident_is_displaced_by_poor_incoming() {
+        let mut m = IdentityMap::default();
+        m.insert(1, 0);
+        m.insert(17, 0);
+        m.insert(2, 0);
+        m.insert(33, 0);
+
+        assert_eq!(m.slots[3].as_ref().map(|s| s.key), Some(33));
+        assert_eq!(m.slots[4].as_ref().map(|s| s.key), Some(2));
+        assert_robin_hood_invariant(&m);
+    }
+
+    #[test]
+    fn backward_shift_deletion_leaves_no_gaps() {
+        let mut m = IdentityMap::default();
+        for k in [1, 17, 33, 49] {
+            m.insert(k, k);
+        }
+
+        assert_eq!(m.remove(&17), Some(17));
+        assert_eq!(m.slots[2].as_ref().map(|s| s.key), Some(33));
+        assert_eq!(m.slots[3].as_ref().map(|s| s.key), Some(49));
+        assert!(m.slots[4].is_none());
+        assert_robin_hood_invariant(&m);
+
+        assert_eq!(m.get(&33), Some(&33));
+        assert_eq!(m.get(&49), Some(&49));
+    }
+
+    #[test]
+    fn probing_wraps_around_end_of_table() {
+        let mut m = IdentityMap::default();
+        m.insert(15, 1);
+        m.insert(31, 2);
+        m.insert(47, 3);
+
+        assert_eq!(m.slots[0].as_ref().map(|s| s.key), Some(31));
+        assert_eq!(m.get(&47), Some(&3));
+        assert_eq!(m.remove(&15), Some(1));
+        assert_eq!(m.slots[15].as_ref().map(|s| s.key), Some(31));
+        assert_robin_hood_invariant(&m);
+    }
+
+    #[test]
+    fn invariant_holds_through_random_workload() {
+        let mut m = RobinHoodMap::<u64, u64>::with_seed(3);
+        let mut rng = Lcg::new(12345);
+        for _ in 0..5000 {
+            let key = rng.below(500);
+            if rng.below(3) == 0 {
+                m.remove(&key);
+            } else {
+                m.insert(key, rng.next_u64());
+            }
+        }
+        assert!(m.len() > 100);
+        assert_robin_hood_invariant(&m);
+    }
+
+    #[test]
+    fn load_factor_stays_below_seven_eighths() {
+        let mut m = RobinHoodMap::<u64, u64>::new();
+        for i in 0..10_000 {
+            m.insert(i, i);
+            assert!(m.len() * 8 <= m.slots.len() * 7);
+        }
+    }
+}
@@ -0,0 +1,457 @@
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+
+use crate::{DefaultHashBuilder, RandomState};
+
+const GROUP_WIDTH: usize = 8;
+const SWISS_INITIAL_CAPACITY: usize = 16;
+
+const EMPTY: u8 = 0b1111_1111;
+const DELETED: u8 = 0b1000_0000;
+
+const LSB: u64 = 0x0101_0101_0101_0101;
+const MSB: u64 = 0x8080_8080_8080_8080;
+
+#[derive(Clone, Copy)]
+struct Group(u64);
+
+impl Group {
+    fn load(ctrl: &[u8], start: usize) -> Self {
+        let bytes: [u8; GROUP_WIDTH] = ctrl[start..start + GROUP_WIDTH]
+            .try_into()
+            .expect("group has GROUP_WIDTH bytes");
+        Group(u64::from_le_bytes(bytes))
+    }
+
+    fn match_byte(self, byte: u8) -> BitMask {
+        let x = self.0 ^ (LSB * byte as u64);
+        BitMask(x.wrapping_sub(LSB) & !x & MSB)
+    }
+
+    fn match_empty(self) -> BitMask {
+        BitMask(self.0 & (self.0 << 1) & MSB)
+    }
+
+    fn match_empty_or_deleted(self) -> BitMask {
+        BitMask(self.0 & MSB)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct BitMask(u64);
+
+impl BitMask {
+    fn any(self) -> bool {
+        self.0 != 0
+    }
+}
+
+impl Iterator for BitMask {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.0 == 0 {
+            return None;
+        }
+        let index = self.0.trailing_zeros() as usize / 8;
+        self.0 &= self.0 - 1;
+        Some(index)
+    }
+}
+
+struct ProbeSeq {
+    group: usize,
+    stride: usize,
+    mask: usize,
+}
+
+impl Iterator for ProbeSeq {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let current = self.group;
+        self.stride += 1;
+        self.group = (self.group + self.stride) & self.mask;
+        Some(current * GROUP_WIDTH)
+    }
+}
+
+pub struct SwissMap<K, V, S = DefaultHashBuilder> {
+    ctrl: Vec<u8>,
+    keys: Vec<Option<K>>,
+    values: Vec<Option<V>>,
+    counter: usize,
+    growth_left: usize,
+    hash_builder: S,
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> Default for SwissMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<K: Eq + Hash, V> SwissMap<K, V> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_hasher(RandomState::with_seed(seed))
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> SwissMap<K, V, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        let mut slots = SWISS_INITIAL_CAPACITY;
+        while capacity > max_load(slots) {
+            slots *= 2;
+        }
+
+        Self {
+            ctrl: vec![EMPTY; slots],
+            keys: (0..slots).map(|_| None).collect(),
+            values: (0..slots).map(|_| None).collect(),
+            counter: 0,
+            growth_left: max_load(slots),
+            hash_builder,
+        }
+    }
+
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    pub fn len(&self) -> usize {
+        self.counter
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counter == 0
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get(key).is_some()
+    }
+
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let slot = self.find(self.hash_builder.hash_one(key), key)?;
+        self.values[slot].as_ref()
+    }
+
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let hash = self.hash_builder.hash_one(&key);
+
+        if let Some(slot) = self.find(hash, &key) {
+            return self.values[slot].replace(value);
+        }
+
+        let mut slot = self.find_insert_slot(hash);
+        if self.ctrl[slot] == EMPTY && self.growth_left == 0 {
+            self.rehash();
+            slot = self.find_insert_slot(hash);
+        }
+
+        self.set_full(slot, hash, key, value);
+        None
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let slot = self.find(self.hash_builder.hash_one(key), key)?;
+        let group_start = slot - slot % GROUP_WIDTH;
+
+        if Group::load(&self.ctrl, group_start).match_empty().any() {
+            self.ctrl[slot] = EMPTY;
+            self.growth_left += 1;
+        } else {
+            self.ctrl[slot] = DELETED;
+        }
+
+        self.keys[slot] = None;
+        self.counter -= 1;
+        self.values[slot].take()
+    }
+
+    pub fn clear(&mut self) {
+        self.ctrl.fill(EMPTY);
+        self.keys.iter_mut().for_each(|k| *k = None);
+        self.values.iter_mut().for_each(|v| *v = None);
+        self.counter = 0;
+        self.growth_left = max_load(self.ctrl.len());
+    }
+
+    fn probe_seq(&self, hash: u64) -> ProbeSeq {
+        let mask = self.ctrl.len() / GROUP_WIDTH - 1;
+        ProbeSeq {
+            group: h1(hash) & mask,
+            stride: 0,
+            mask,
+        }
+    }
+
+    fn find<Q>(&self, hash: u64, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq,
+    {
+        let tag = h2(hash);
+
+        for start in self.probe_seq(hash) {
+            let group = Group::load(&self.ctrl, start);
+
+            for offset in group.match_byte(tag) {
+                let slot = start + offset;
+                if self.keys[slot].as_ref().is_some_and(|k| k.borrow() == key) {
+                    return Some(slot);
+                }
+            }
+
+            if group.match_empty().any() {
+                return None;
+            }
+        }
+
+        None
+    }
+
+    fn find_insert_slot(&self, hash: u64) -> usize {
+        for start in self.probe_seq(hash) {
+            let group = Group::load(&self.ctrl, start);
+            if let Some(offset) = group.match_empty_or_deleted().next() {
+                return start + offset;
+            }
+        }
+
+        unreachable!("the table always keeps at least one empty slot")
+    }
+
+    fn set_full(&mut self, slot: usize, hash: u64, key: K, value: V) {
+        if self.ctrl[slot] == EMPTY {
+            self.growth_left -= 1;
+        }
+        self.ctrl[slot] = h2(hash);
+        self.keys[slot] = Some(key);
+        self.values[slot] = Some(value);
+        self.counter += 1;
+    }
+
+    fn rehash(&mut self) {
+        let slots = self.ctrl.len();
+        let new_slots = if self.counter + 1 > max_load(slots) / 2 {
+            slots * 2
+        } else {
+            slots
+        };
+
+        let old_keys = std::mem::replace(&mut self.keys, (0..new_slots).map(|_| None).collect());
+        let old_values =
+            std::mem::replace(&mut self.values, (0..new_slots).map(|_| None).collect());
+        self.ctrl = vec![EMPTY; new_slots];
+        self.counter = 0;
+        self.growth_left = max_load(new_slots);
+
+        for (key, value) in old_keys.into_iter().zip(old_values) {
+            if let (Some(key), Some(value)) = (key, value) {
+                let hash = self.hash_builder.hash_one(&key);
+                let slot = self.find_insert_slot(hash);
+                self.set_full(slot, hash, key, value);
+            }
+        }
+    }
+}
+
+fn max_load(slots: usize) -> usize {
+    slots / 8 * 7
+}
+
+fn h1(hash: u64) -> usize {
+    hash as usize
+}
+
+fn h2(hash: u64) -> u8 {
+    (hash >> 57) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{BuildHasherDefault, Hasher};
+
+    use super::*;
+    use crate::HashMap;
+    use crate::fixtures::{IdentityHasher, Lcg};
+
+    map_test_suite!(SwissMap);
+
+    #[derive(Default)]
+    struct GroupZeroHasher(IdentityHasher);
+
+    impl Hasher for GroupZeroHasher {
+        fn finish(&self) -> u64 {
+            self.0.finish() << 57
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.write(bytes);
+        }
+
+        fn write_u64(&mut self, n: u64) {
+            self.0.write_u64(n);
+        }
+    }
+
+    type GroupZeroMap = SwissMap<u64, u64, BuildHasherDefault<GroupZeroHasher>>;
+
+    fn group(bytes: [u8; 8]) -> Group {
+        Group(u64::from_le_bytes(bytes))
+    }
+
+    #[test]
+    fn match_byte_finds_every_matching_position() {
+        let g = group([0x12, EMPTY, 0x12, 0x05, DELETED, 0x12, 0x00, 0x7f]);
+        assert_eq!(g.match_byte(0x12).collect::<Vec<_>>(), vec![0, 2, 5]);
+        assert_eq!(g.match_byte(0x7f).collect::<Vec<_>>(), vec![7]);
+        assert!(g.match_byte(0x33).next().is_none());
+    }
+
+    #[test]
+    fn match_empty_ignores_deleted_and_full() {
+        let g = group([0x12, EMPTY, DELETED, 0x05, EMPTY, 0x00, 0x7f, DELETED]);
+        assert_eq!(g.match_empty().collect::<Vec<_>>(), vec![1, 4]);
+    }
+
+    #[test]
+    fn match_empty_or_deleted_finds_both_markers() {
+        let g = group([0x12, EMPTY, DELETED, 0x05, EMPTY, 0x00, 0x7f, DELETED]);
+        assert_eq!(
+            g.match_empty_or_deleted().collect::<Vec<_>>(),
+            vec![1, 2, 4, 7]
+        );
+    }
+
+    #[test]
+    fn full_control_bytes_never_have_high_bit() {
+        for hash in [0u64, u64::MAX, 1 << 63, 0x1234_5678_9abc_def0] {
+            assert_eq!(h2(hash) & 0x80, 0);
+        }
+    }
+
+    #[test]
+    fn probe_sequence_visits_every_group() {
+        let mut seen: Vec<usize> = ProbeSeq {
+            group: 5,
+            stride: 0,
+            mask: 15,
+        }
+        .take(16)
+        .collect();
+        seen.sort();
+        assert_eq!(seen, (0..16).map(|g| g * GROUP_WIDTH).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn remove_in_group_with_empty_slot_marks_empty() {
+        let mut m = GroupZeroMap::default();
+        m.insert(1, 10);
+        m.insert(2, 20);
+
+        let slot = m.find(m.hash_builder.hash_one(1u64), &1).unwrap();
+        m.remove(&1);
+        assert_eq!(m.ctrl[slot], EMPTY);
+        assert_eq!(m.get(&2), Some(&20));
+    }
+
+    #[test]
+    fn remove_in_full_group_leaves_tombstone() {
+        let mut m = GroupZeroMap::default();
+        for k in 0..9 {
+            m.insert(k, k);
+        }
+
+        let slot = m.find(m.hash_builder.hash_one(3u64), &3).unwrap();
+        assert!(slot < GROUP_WIDTH);
+        m.remove(&3);
+        assert_eq!(m.ctrl[slot], DELETED);
+
+        assert_eq!(m.get(&8), Some(&8));
+        for k in (0..9).filter(|&k| k != 3) {
+            assert_eq!(m.get(&k), Some(&k));
+        }
+    }
+
+    #[test]
+    fn tombstones_are_reused_by_insert() {
+        let mut m = GroupZeroMap::default();
+        for k in 0..9 {
+            m.insert(k, k);
+        }
+        let slot = m.find(m.hash_builder.hash_one(3u64), &3).unwrap();
+        m.remove(&3);
+
+        m.insert(100, 100);
+        assert_eq!(m.find(m.hash_builder.hash_one(100u64), &100), Some(slot));
+    }
+
+    #[test]
+    fn churn_with_tombstones_keeps_table_usable() {
+        let mut m = SwissMap::<u64, u64>::with_seed(9);
+        for round in 0..50u64 {
+            for k in 0..100 {
+                m.insert(round * 1000 + k, k);
+            }
+            for k in 0..100 {
+                assert_eq!(m.remove(&(round * 1000 + k)), Some(k));
+            }
+        }
+        assert!(m.is_empty());
+        assert!(m.ctrl.len() <= 256);
+    }
+
+    #[test]
+    fn matches_chained_hash_map_on_random_workload() {
+        let mut swiss = SwissMap::<u64, u64>::with_seed(1);
+        let mut reference = HashMap::<u64, u64>::with_seed(1);
+        let mut rng = Lcg::new(99);
+
+        for _ in 0..20_000 {
+            let key = rng.below(1000);
+            if rng.below(3) == 0 {
+                assert_eq!(swiss.remove(&key), reference.remove(&key));
+            } else {
+                let value = rng.next_u64();
+                swiss.insert(key, value);
+                reference.insert(key, value);
+            }
+            assert_eq!(swiss.len(), reference.len());
+        }
+
+        for key in 0..1000 {
+            assert_eq!(swiss.get(&key), reference.get(&key));
+        }
+    }
+
+    #[test]
+    fn load_never_exceeds_seven_eighths() {
+        let mut m = SwissMap::<u64, u64>::new();
+        for i in 0..5000 {
+            m.insert(i, i);
+            let used = m.ctrl.iter().filter(|&&c| c != EMPTY).count();
+            assert!(used <= max_load(m.ctrl.len()));
+        }
+    }
+}
@@ -0,0 +1,314 @@
+macro_rules! map_test_suite {
+    ($map:ident) => {
+        #[test]
+        fn len_returns_zero_for_new_hash_map() {
+            let h = $map::<String, String>::new();
+            assert_eq!(h.len(), 0);
+        }
+
+        #[test]
+        fn is_empty_returns_true_for_new_hash_map() {
+            let h = $map::<String, String>::new();
+            assert!(h.is_empty());
+        }
+
+        #[test]
+        fn insert_increases_len_from_zero_to_one() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+            assert_eq!(h.len(), 1);
+            assert!(!h.is_empty());
+        }
+
+        #[test]
+        fn insert_multiple_distinct_keys_increases_len_each_time() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+            h.insert("b".to_string(), "2".to_string());
+            h.insert("c".to_string(), "3".to_string());
+            assert_eq!(h.len(), 3);
+        }
+
+        #[test]
+        fn insert_same_key_twice_does_not_increase_len() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+            h.insert("a".to_string(), "2".to_string());
+            assert_eq!(h.len(), 1);
+        }
+
+        #[test]
+        fn insert_same_key_many_times_keeps_len_one() {
+            let mut h = $map::<String, String>::new();
+            for i in 0..50 {
+                h.insert("a".to_string(), i.to_string());
+            }
+            assert_eq!(h.len(), 1);
+            assert!(!h.is_empty());
+        }
+
+        #[test]
+        fn insert_many_keys_len_matches_count() {
+            let mut h = $map::<String, String>::new();
+            for i in 0..100 {
+                h.insert(format!("k{i}"), format!("v{i}"));
+            }
+            assert_eq!(h.len(), 100);
+        }
+
+        #[test]
+        fn get_returns_none_for_empty_hash_map() {
+            assert!($map::<String, String>::new().get("a").is_none());
+        }
+
+        #[test]
+        fn get_returns_some_after_insert() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+            assert_eq!(h.get("a"), Some(&"1".to_string()));
+        }
+
+        #[test]
+        fn get_returns_none_for_missing_key() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+            assert!(h.get("b").is_none());
+        }
+
+        #[test]
+        fn get_returns_updated_value_for_same_key() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+            h.insert("a".to_string(), "2".to_string());
+            assert_eq!(h.get("a"), Some(&"2".to_string()));
+        }
+
+        #[test]
+        fn get_works_for_many_keys() {
+            let mut h = $map::<String, String>::new();
+            for i in 0..100 {
+                h.insert(format!("k{i}"), format!("v{i}"));
+            }
+            assert_eq!(h.get("k0"), Some(&"v0".to_string()));
+            assert_eq!(h.get("k50"), Some(&"v50".to_string()));
+            assert_eq!(h.get("k99"), Some(&"v99".to_string()));
+            assert!(h.get("k100").is_none());
+        }
+
+        #[test]
+        fn remove_returns_none_for_empty_hash_map() {
+            let mut h = $map::<String, String>::new();
+            assert!(h.remove("a").is_none());
+            assert_eq!(h.len(), 0);
+            assert!(h.is_empty());
+        }
+
+        #[test]
+        fn remove_returns_some_and_decreases_len() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+
+            let removed = h.remove("a");
+            assert_eq!(removed, Some("1".to_string()));
+            assert_eq!(h.len(), 0);
+            assert!(h.is_empty());
+        }
+
+        #[test]
+        fn remove_missing_key_returns_none_and_len_unchanged() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+            h.insert("b".to_string(), "2".to_string());
+
+            let removed = h.remove("c");
+            assert!(removed.is_none());
+            assert_eq!(h.len(), 2);
+        }
+
+        #[test]
+        fn remove_then_get_returns_none() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+
+            assert!(h.remove("a").is_some());
+            assert!(h.get("a").is_none());
+            assert_eq!(h.len(), 0);
+        }
+
+        #[test]
+        fn remove_only_removes_one_key() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+            h.insert("b".to_string(), "2".to_string());
+            h.insert("c".to_string(), "3".to_string());
+
+            assert_eq!(h.remove("b"), Some("2".to_string()));
+            assert_eq!(h.len(), 2);
+
+            assert_eq!(h.get("a"), Some(&"1".to_string()));
+            assert!(h.get("b").is_none());
+            assert_eq!(h.get("c"), Some(&"3".to_string()));
+        }
+
+        #[test]
+        fn remove_same_key_twice_second_time_returns_none() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+
+            assert_eq!(h.remove("a"), Some("1".to_string()));
+            assert!(h.remove("a").is_none());
+            assert_eq!(h.len(), 0);
+        }
+
+        #[test]
+        fn remove_many_keys_len_matches_remaining() {
+            let mut h = $map::<String, String>::new();
+
+            for i in 0..50 {
+                h.insert(format!("k{i}"), format!("v{i}"));
+            }
+            assert_eq!(h.len(), 50);
+
+            for i in 0..20 {
+                assert_eq!(h.remove(&format!("k{i}")), Some(format!("v{i}")));
+            }
+
+            assert_eq!(h.len(), 30);
+            for i in 0..20 {
+                assert!(h.get(&format!("k{i}")).is_none());
+            }
+            for i in 20..50 {
+                assert_eq!(h.get(&format!("k{i}")), Some(&format!("v{i}")));
+            }
+        }
+
+        #[test]
+        fn contains_returns_false_for_empty_hash_map() {
+            let h = $map::<String, String>::new();
+            assert!(!h.contains("a"));
+        }
+
+        #[test]
+        fn contains_returns_true_after_insert() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+            assert!(h.contains("a"));
+        }
+
+        #[test]
+        fn contains_returns_false_for_missing_key() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+            assert!(!h.contains("b"));
+        }
+
+        #[test]
+        fn contains_still_true_after_value_update_same_key() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+            h.insert("a".to_string(), "2".to_string());
+            assert!(h.contains("a"));
+            assert_eq!(h.len(), 1);
+        }
+
+        #[test]
+        fn clear_makes_hash_map_empty() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+            h.insert("b".to_string(), "2".to_string());
+            h.insert("c".to_string(), "3".to_string());
+
+            h.clear();
+
+            assert_eq!(h.len(), 0);
+            assert!(h.is_empty());
+            assert!(!h.contains("a"));
+            assert!(!h.contains("b"));
+            assert!(!h.contains("c"));
+        }
+
+        #[test]
+        fn clear_on_empty_hash_map_keeps_it_empty() {
+            let mut h = $map::<String, String>::new();
+            h.clear();
+            assert_eq!(h.len(), 0);
+            assert!(h.is_empty());
+        }
+
+        #[test]
+        fn can_insert_after_clear() {
+            let mut h = $map::<String, String>::new();
+            h.insert("a".to_string(), "1".to_string());
+            h.clear();
+
+            h.insert("x".to_string(), "9".to_string());
+
+            assert_eq!(h.len(), 1);
+            assert!(h.contains("x"));
+            assert_eq!(h.get("x"), Some(&"9".to_string()));
+        }
+
+        #[test]
+        fn lookups_accept_borrowed_str_for_string_keys() {
+            let mut h = $map::<String, i32>::new();
+            h.insert("a".to_string(), 1);
+
+            let key: &str = "a";
+            assert_eq!(h.get(key), Some(&1));
+            assert!(h.contains(key));
+            assert_eq!(h.remove(key), Some(1));
+            assert!(!h.contains(key));
+        }
+
+        #[test]
+        fn lookups_accept_owned_key_by_reference() {
+            let mut h = $map::<String, i32>::new();
+            h.insert("a".to_string(), 1);
+
+            let key = "a".to_string();
+            assert_eq!(h.get(&key), Some(&1));
+            assert!(h.contains(&key));
+        }
+
+        #[test]
+        fn lookups_accept_slice_for_vec_keys() {
+            let mut h = $map::<Vec<u8>, i32>::new();
+            h.insert(vec![1, 2, 3], 10);
+
+            let key: &[u8] = &[1, 2, 3];
+            assert_eq!(h.get(key), Some(&10));
+            assert!(h.contains(key));
+            assert!(!h.contains(&[1, 2][..]));
+            assert_eq!(h.remove(key), Some(10));
+            assert!(h.is_empty());
+        }
+
+        #[test]
+        fn borrowed_lookups_find_keys_after_resize() {
+            let mut h = $map::<String, usize>::new();
+            for i in 0..200 {
+                h.insert(format!("k{i}"), i);
+            }
+
+            for i in 0..200 {
+                assert_eq!(h.get(format!("k{i}").as_str()), Some(&i));
+            }
+        }
+
+        #[test]
+        fn insert_returns_none_for_new_key() {
+            let mut h = $map::<String, i32>::new();
+            assert_eq!(h.insert("a".to_string(), 1), None);
+        }
+
+        #[test]
+        fn insert_returns_previous_value_for_existing_key() {
+            let mut h = $map::<String, i32>::new();
+            h.insert("a".to_string(), 1);
+            assert_eq!(h.insert("a".to_string(), 2), Some(1));
+            assert_eq!(h.insert("a".to_string(), 3), Some(2));
+            assert_eq!(h.get("a"), Some(&3));
+            assert_eq!(h.len(), 1);
+        }
+    };
+}
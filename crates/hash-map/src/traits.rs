@@ -0,0 +1,153 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::ops::Index;
+
+use crate::HashMap;
+
+impl<K: fmt::Debug, V: fmt::Debug, S> fmt::Debug for HashMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<K, V, S> PartialEq for HashMap<K, V, S>
+where
+    K: Eq + Hash,
+    V: PartialEq,
+    S: BuildHasher,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for HashMap<K, V, S> {}
+
+impl<K, V, S> FromIterator<(K, V)> for HashMap<K, V, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = Self::with_hasher(S::default());
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Extend<(K, V)> for HashMap<K, V, S> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for (k, v) in iter {
+            self.insert(k, v);
+        }
+    }
+}
+
+impl<K: Eq + Hash, V, const N: usize> From<[(K, V); N]> for HashMap<K, V> {
+    fn from(pairs: [(K, V); N]) -> Self {
+        Self::from_iter(pairs)
+    }
+}
+
+impl<K, Q, V, S> Index<&Q> for HashMap<K, V, S>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: ?Sized + Eq + Hash,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("key not found in HashMap")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{HashMap, ResizeMode};
+
+    #[test]
+    fn debug_formats_as_map() {
+        let h = HashMap::from([("a", 1)]);
+        assert_eq!(format!("{h:?}"), r#"{"a": 1}"#);
+        assert_eq!(format!("{:?}", HashMap::<i32, i32>::new()), "{}");
+    }
+
+    #[test]
+    fn clone_is_independent() {
+        let mut a = HashMap::from([(1, 10), (2, 20)]);
+        let b = a.clone();
+
+        a.insert(1, 11);
+        a.remove(&2);
+
+        assert_eq!(b.len(), 2);
+        assert_eq!(b.get(&1), Some(&10));
+        assert_eq!(b.get(&2), Some(&20));
+    }
+
+    #[test]
+    fn clone_keeps_pending_migration() {
+        let mut a = HashMap::<u64, u64>::with_seed(1);
+        a.set_resize_mode(ResizeMode::Incremental { buckets_per_op: 1 });
+        let mut next = 0;
+        while !a.is_resizing() {
+            a.insert(next, next);
+            next += 1;
+        }
+
+        let b = a.clone();
+        assert!(b.is_resizing());
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn eq_ignores_insertion_order_and_hasher_seed() {
+        let mut a = HashMap::<i32, i32>::with_seed(1);
+        let mut b = HashMap::<i32, i32>::with_seed(2);
+        for i in 0..100 {
+            a.insert(i, i * 2);
+            b.insert(99 - i, (99 - i) * 2);
+        }
+        assert_eq!(a, b);
+
+        b.insert(0, 1);
+        assert_ne!(a, b);
+        b.insert(0, 0);
+        b.insert(100, 200);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn collect_and_extend() {
+        let mut h: HashMap<i32, i32> = (0..10).map(|i| (i, i * i)).collect();
+        h.extend([(3, 0), (10, 100)]);
+
+        assert_eq!(h.len(), 11);
+        assert_eq!(h[&3], 0);
+        assert_eq!(h[&10], 100);
+    }
+
+    #[test]
+    fn from_array_keeps_last_duplicate() {
+        let h = HashMap::from([("a", 1), ("b", 2), ("a", 3)]);
+        assert_eq!(h.len(), 2);
+        assert_eq!(h["a"], 3);
+    }
+
+    #[test]
+    fn index_accepts_borrowed_key() {
+        let h = HashMap::from([("a".to_string(), 1)]);
+        assert_eq!(h["a"], 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "key not found")]
+    fn index_panics_on_missing_key() {
+        let h = HashMap::from([("a", 1)]);
+        let _ = h["b"];
+    }
+}
@@ -0,0 +1,253 @@
+use std::hash::{BuildHasher, Hash};
+use std::iter::{Chain, FusedIterator};
+
+use hash_map::{IntoKeys, Keys};
+
+use crate::HashSet;
+
+pub struct Iter<'a, T> {
+    inner: Keys<'a, T, ()>,
+}
+
+pub struct IntoIter<T> {
+    inner: IntoKeys<T, ()>,
+}
+
+pub struct Union<'a, T, S> {
+    inner: Chain<Iter<'a, T>, Difference<'a, T, S>>,
+}
+
+pub struct Intersection<'a, T, S> {
+    iter: Iter<'a, T>,
+    other: &'a HashSet<T, S>,
+}
+
+pub struct Difference<'a, T, S> {
+    iter: Iter<'a, T>,
+    other: &'a HashSet<T, S>,
+}
+
+pub struct SymmetricDifference<'a, T, S> {
+    inner: Chain<Difference<'a, T, S>, Difference<'a, T, S>>,
+}
+
+impl<T, S> HashSet<T, S> {
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            inner: self.map.keys(),
+        }
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher> HashSet<T, S> {
+    pub fn union<'a>(&'a self, other: &'a Self) -> Union<'a, T, S> {
+        let (big, small) = if self.len() >= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        Union {
+            inner: big.iter().chain(small.difference(big)),
+        }
+    }
+
+    pub fn intersection<'a>(&'a self, other: &'a Self) -> Intersection<'a, T, S> {
+        let (small, big) = if self.len() <= other.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+
+        Intersection {
+            iter: small.iter(),
+            other: big,
+        }
+    }
+
+    pub fn difference<'a>(&'a self, other: &'a Self) -> Difference<'a, T, S> {
+        Difference {
+            iter: self.iter(),
+            other,
+        }
+    }
+
+    pub fn symmetric_difference<'a>(&'a self, other: &'a Self) -> SymmetricDifference<'a, T, S> {
+        SymmetricDifference {
+            inner: self.difference(other).chain(other.difference(self)),
+        }
+    }
+}
+
+impl<T, S> IntoIterator for HashSet<T, S> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.map.into_keys(),
+        }
+    }
+}
+
+impl<'a, T, S> IntoIterator for &'a HashSet<T, S> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T: Eq + Hash, S: BuildHasher> Iterator for Union<'a, T, S> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T: Eq + Hash, S: BuildHasher> Iterator for Intersection<'a, T, S> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.by_ref().find(|v| self.other.contains(*v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<'a, T: Eq + Hash, S: BuildHasher> Iterator for Difference<'a, T, S> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.by_ref().find(|v| !self.other.contains(*v))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<'a, T: Eq + Hash, S: BuildHasher> Iterator for SymmetricDifference<'a, T, S> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {}
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+impl<T> FusedIterator for IntoIter<T> {}
+impl<T: Eq + Hash, S: BuildHasher> FusedIterator for Union<'_, T, S> {}
+impl<T: Eq + Hash, S: BuildHasher> FusedIterator for Intersection<'_, T, S> {}
+impl<T: Eq + Hash, S: BuildHasher> FusedIterator for Difference<'_, T, S> {}
+impl<T: Eq + Hash, S: BuildHasher> FusedIterator for SymmetricDifference<'_, T, S> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::HashSet;
+
+    fn set(values: &[i32]) -> HashSet<i32> {
+        values.iter().copied().collect()
+    }
+
+    fn sorted<'a>(iter: impl Iterator<Item = &'a i32>) -> Vec<i32> {
+        let mut v: Vec<i32> = iter.copied().collect();
+        v.sort();
+        v
+    }
+
+    #[test]
+    fn iter_visits_every_value_once() {
+        let s = set(&[3, 1, 2]);
+        assert_eq!(s.iter().len(), 3);
+        assert_eq!(sorted(s.iter()), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn into_iter_yields_owned_values() {
+        let mut v: Vec<i32> = set(&[3, 1, 2]).into_iter().collect();
+        v.sort();
+        assert_eq!(v, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn union_yields_each_value_once() {
+        let a = set(&[1, 2, 3]);
+        let b = set(&[3, 4]);
+        assert_eq!(sorted(a.union(&b)), vec![1, 2, 3, 4]);
+        assert_eq!(sorted(b.union(&a)), vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn intersection_yields_common_values() {
+        let a = set(&[1, 2, 3, 4]);
+        let b = set(&[2, 4, 6]);
+        assert_eq!(sorted(a.intersection(&b)), vec![2, 4]);
+        assert_eq!(sorted(b.intersection(&a)), vec![2, 4]);
+    }
+
+    #[test]
+    fn difference_yields_values_only_in_self() {
+        let a = set(&[1, 2, 3]);
+        let b = set(&[2, 3, 4]);
+        assert_eq!(sorted(a.difference(&b)), vec![1]);
+        assert_eq!(sorted(b.difference(&a)), vec![4]);
+    }
+
+    #[test]
+    fn symmetric_difference_yields_values_in_exactly_one_set() {
+        let a = set(&[1, 2, 3]);
+        let b = set(&[2, 3, 4]);
+        assert_eq!(sorted(a.symmetric_difference(&b)), vec![1, 4]);
+    }
+
+    #[test]
+    fn set_algebra_is_lazy() {
+        let a: HashSet<i32> = (0..1000).collect();
+        let b: HashSet<i32> = (500..1500).collect();
+
+        let mut inter = a.intersection(&b);
+        let first = *inter.next().unwrap();
+        assert!((500..1000).contains(&first));
+        assert_eq!(inter.count(), 499);
+    }
+}
@@ -0,0 +1,217 @@
+mod iter;
+mod ops;
+
+pub use iter::{Difference, Intersection, IntoIter, Iter, SymmetricDifference, Union};
+
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, Hash};
+
+use hash_map::{DefaultHashBuilder, HashMap, RandomState};
+
+#[derive(Clone)]
+pub struct HashSet<T, S = DefaultHashBuilder> {
+    map: HashMap<T, (), S>,
+}
+
+impl<T: Eq + Hash, S: BuildHasher + Default> Default for HashSet<T, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<T: Eq + Hash> HashSet<T> {
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        Self::with_hasher(RandomState::with_seed(seed))
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, RandomState::new())
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher> HashSet<T, S> {
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self {
+            map: HashMap::with_hasher(hash_builder),
+        }
+    }
+
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        Self {
+            map: HashMap::with_capacity_and_hasher(capacity, hash_builder),
+        }
+    }
+
+    pub fn hasher(&self) -> &S {
+        self.map.hasher()
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn insert(&mut self, value: T) -> bool {
+        self.map.insert(value, ()).is_none()
+    }
+
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.contains(value)
+    }
+
+    pub fn get<Q>(&self, value: &Q) -> Option<&T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.get_key_value(value).map(|(k, _)| k)
+    }
+
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    pub fn take<Q>(&mut self, value: &Q) -> Option<T>
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.remove_entry(value).map(|(k, _)| k)
+    }
+
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.map.retain(|k, _| f(k));
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.len() <= other.len() && self.iter().all(|v| other.contains(v))
+    }
+
+    pub fn is_superset(&self, other: &Self) -> bool {
+        other.is_subset(self)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        self.intersection(other).next().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HashSet;
+
+    fn set(values: &[i32]) -> HashSet<i32> {
+        values.iter().copied().collect()
+    }
+
+    #[test]
+    fn new_set_is_empty() {
+        let s = HashSet::<i32>::new();
+        assert!(s.is_empty());
+        assert_eq!(s.len(), 0);
+        assert!(!s.contains(&1));
+    }
+
+    #[test]
+    fn insert_reports_whether_value_was_new() {
+        let mut s = HashSet::new();
+        assert!(s.insert(1));
+        assert!(!s.insert(1));
+        assert!(s.insert(2));
+        assert_eq!(s.len(), 2);
+    }
+
+    #[test]
+    fn remove_reports_whether_value_was_present() {
+        let mut s = set(&[1, 2]);
+        assert!(s.remove(&1));
+        assert!(!s.remove(&1));
+        assert_eq!(s.len(), 1);
+        assert!(s.contains(&2));
+    }
+
+    #[test]
+    fn take_returns_stored_value() {
+        let mut s = HashSet::<String>::new();
+        s.insert("a".to_string());
+
+        assert_eq!(s.get("a"), Some(&"a".to_string()));
+        assert_eq!(s.take("a"), Some("a".to_string()));
+        assert_eq!(s.take("a"), None);
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    fn retain_keeps_matching_values() {
+        let mut s = set(&[1, 2, 3, 4, 5, 6]);
+        s.retain(|v| v % 2 == 0);
+        assert_eq!(s, set(&[2, 4, 6]));
+    }
+
+    #[test]
+    fn clear_removes_all_values() {
+        let mut s = set(&[1, 2, 3]);
+        s.clear();
+        assert!(s.is_empty());
+        assert!(!s.contains(&1));
+    }
+
+    #[test]
+    fn subset_and_superset() {
+        let small = set(&[1, 2]);
+        let big = set(&[1, 2, 3]);
+
+        assert!(small.is_subset(&big));
+        assert!(!big.is_subset(&small));
+        assert!(big.is_superset(&small));
+        assert!(small.is_subset(&small));
+        assert!(HashSet::new().is_subset(&small));
+    }
+
+    #[test]
+    fn disjoint_sets_share_no_values() {
+        assert!(set(&[1, 2]).is_disjoint(&set(&[3, 4])));
+        assert!(!set(&[1, 2]).is_disjoint(&set(&[2, 3])));
+        assert!(HashSet::<i32>::new().is_disjoint(&HashSet::new()));
+    }
+
+    #[test]
+    fn dedup_through_collect() {
+        let s: HashSet<&str> = ["read", "write", "read", "admin", "write"]
+            .into_iter()
+            .collect();
+        assert_eq!(s.len(), 3);
+    }
+
+    #[test]
+    fn many_values_survive_resize() {
+        let mut s = HashSet::with_capacity(4);
+        for i in 0..1000 {
+            assert!(s.insert(i));
+        }
+        assert_eq!(s.len(), 1000);
+        assert!((0..1000).all(|i| s.contains(&i)));
+    }
+}
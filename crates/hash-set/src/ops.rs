@@ -0,0 +1,151 @@
+use std::fmt;
+use std::hash::{BuildHasher, Hash};
+use std::ops::{BitAnd, BitOr, BitXor, Sub};
+
+use crate::HashSet;
+
+impl<T: fmt::Debug, S> fmt::Debug for HashSet<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher> PartialEq for HashSet<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.is_subset(other)
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher> Eq for HashSet<T, S> {}
+
+impl<T: Eq + Hash, S: BuildHasher + Default> FromIterator<T> for HashSet<T, S> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set = Self::with_hasher(S::default());
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher> Extend<T> for HashSet<T, S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.map.extend(iter.into_iter().map(|v| (v, ())));
+    }
+}
+
+impl<T: Eq + Hash, const N: usize> From<[T; N]> for HashSet<T> {
+    fn from(values: [T; N]) -> Self {
+        Self::from_iter(values)
+    }
+}
+
+impl<T, S> BitOr<&HashSet<T, S>> for &HashSet<T, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher + Default,
+{
+    type Output = HashSet<T, S>;
+
+    fn bitor(self, rhs: &HashSet<T, S>) -> HashSet<T, S> {
+        self.union(rhs).cloned().collect()
+    }
+}
+
+impl<T, S> BitAnd<&HashSet<T, S>> for &HashSet<T, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher + Default,
+{
+    type Output = HashSet<T, S>;
+
+    fn bitand(self, rhs: &HashSet<T, S>) -> HashSet<T, S> {
+        self.intersection(rhs).cloned().collect()
+    }
+}
+
+impl<T, S> Sub<&HashSet<T, S>> for &HashSet<T, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher + Default,
+{
+    type Output = HashSet<T, S>;
+
+    fn sub(self, rhs: &HashSet<T, S>) -> HashSet<T, S> {
+        self.difference(rhs).cloned().collect()
+    }
+}
+
+impl<T, S> BitXor<&HashSet<T, S>> for &HashSet<T, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher + Default,
+{
+    type Output = HashSet<T, S>;
+
+    fn bitxor(self, rhs: &HashSet<T, S>) -> HashSet<T, S> {
+        self.symmetric_difference(rhs).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::HashSet;
+
+    #[test]
+    fn debug_formats_as_set() {
+        assert_eq!(format!("{:?}", HashSet::from([1])), "{1}");
+        assert_eq!(format!("{:?}", HashSet::<i32>::new()), "{}");
+    }
+
+    #[test]
+    fn eq_ignores_order_and_seed() {
+        let mut a = HashSet::with_seed(1);
+        let mut b = HashSet::with_seed(2);
+        for i in 0..50 {
+            a.insert(i);
+            b.insert(49 - i);
+        }
+        assert_eq!(a, b);
+
+        b.insert(50);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn clone_is_independent() {
+        let mut a = HashSet::from([1, 2]);
+        let b = a.clone();
+        a.insert(3);
+        assert_eq!(b, HashSet::from([1, 2]));
+    }
+
+    #[test]
+    fn extend_skips_duplicates() {
+        let mut s = HashSet::from([1, 2]);
+        s.extend([2, 3, 3]);
+        assert_eq!(s, HashSet::from([1, 2, 3]));
+    }
+
+    #[test]
+    fn operators_build_new_sets() {
+        let a = HashSet::from([1, 2, 3]);
+        let b = HashSet::from([3, 4]);
+
+        assert_eq!(&a | &b, HashSet::from([1, 2, 3, 4]));
+        assert_eq!(&a & &b, HashSet::from([3]));
+        assert_eq!(&a - &b, HashSet::from([1, 2]));
+        assert_eq!(&a ^ &b, HashSet::from([1, 2, 4]));
+
+        assert_eq!(a.len(), 3);
+        assert_eq!(b.len(), 2);
+    }
+
+    #[test]
+    fn permission_check_with_operators() {
+        let required = HashSet::from(["read", "write"]);
+        let granted = HashSet::from(["read", "admin"]);
+
+        let missing = &required - &granted;
+        assert_eq!(missing, HashSet::from(["write"]));
+        assert!(!required.is_subset(&granted));
+    }
+}
@@ -0,0 +1,665 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::Hash;
+
+use hash_map::HashMap;
+
+const NIL: usize = usize::MAX;
+const NO_FREQ: u64 = 0;
+
+type EvictionListener<K, V> = Box<dyn FnMut(K, V)>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct Stats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+}
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    freq: u64,
+    prev: usize,
+    next: usize,
+}
+
+#[derive(Clone, Copy)]
+struct FreqList {
+    head: usize,
+    tail: usize,
+    lower: u64,
+    higher: u64,
+}
+
+pub struct LfuCache<K, V> {
+    map: HashMap<K, usize>,
+    nodes: Vec<Option<Node<K, V>>>,
+    free: Vec<usize>,
+    lists: HashMap<u64, FreqList>,
+    lowest: u64,
+    capacity: usize,
+    stats: Stats,
+    decay_interval: Option<u64>,
+    ops: u64,
+    on_evict: Option<EvictionListener<K, V>>,
+}
+
+impl<K: Eq + Hash + Clone, V> LfuCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        Self {
+            map: HashMap::with_capacity(capacity),
+            nodes: Vec::new(),
+            free: Vec::new(),
+            lists: HashMap::new(),
+            lowest: NO_FREQ,
+            capacity,
+            stats: Stats::default(),
+            decay_interval: None,
+            ops: 0,
+            on_evict: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = Stats::default();
+    }
+
+    pub fn set_on_evict<F>(&mut self, listener: F)
+    where
+        F: FnMut(K, V) + 'static,
+    {
+        self.on_evict = Some(Box::new(listener));
+    }
+
+    pub fn decay_interval(&self) -> Option<u64> {
+        self.decay_interval
+    }
+
+    pub fn set_decay_interval(&mut self, interval: Option<u64>) {
+        if let Some(interval) = interval {
+            assert!(interval > 0, "decay interval must be positive");
+        }
+        self.decay_interval = interval;
+        self.ops = 0;
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.contains(key)
+    }
+
+    pub fn frequency<Q>(&self, key: &Q) -> Option<u64>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let id = *self.map.get(key)?;
+        Some(self.node(id).freq)
+    }
+
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let id = *self.map.get(key)?;
+        Some(&self.node(id).value)
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let id = self.access(key)?;
+        Some(&self.node(id).value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let id = self.access(key)?;
+        Some(&mut self.node_mut(id).value)
+    }
+
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        self.tick();
+
+        if let Some(id) = self.map.get(&key).copied() {
+            self.touch(id);
+            return Some(std::mem::replace(&mut self.node_mut(id).value, value));
+        }
+
+        if self.len() == self.capacity {
+            self.evict();
+        }
+
+        let id = self.alloc(Node {
+            key: key.clone(),
+            value,
+            freq: 1,
+            prev: NIL,
+            next: NIL,
+        });
+        self.map.insert(key, id);
+        self.link_back(id, NO_FREQ);
+        None
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let id = *self.map.get(key)?;
+        Some(self.remove_node(id).1)
+    }
+
+    pub fn pop_lfu(&mut self) -> Option<(K, V)> {
+        let id = self.lists.get(&self.lowest)?.head;
+        Some(self.remove_node(id))
+    }
+
+    pub fn decay(&mut self) {
+        let mut order = Vec::with_capacity(self.len());
+        let mut freq = self.lowest;
+        while freq != NO_FREQ {
+            let list = self.lists[&freq];
+            let mut id = list.head;
+            while id != NIL {
+                order.push(id);
+                id = self.node(id).next;
+            }
+            freq = list.higher;
+        }
+
+        self.lists.clear();
+        self.lowest = NO_FREQ;
+        let mut highest = NO_FREQ;
+        for id in order {
+            let node = self.node_mut(id);
+            node.freq = (node.freq / 2).max(1);
+            let freq = node.freq;
+            self.link_back(id, highest);
+            highest = freq;
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.nodes.clear();
+        self.free.clear();
+        self.lists.clear();
+        self.lowest = NO_FREQ;
+        self.ops = 0;
+    }
+
+    fn access<Q>(&mut self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.tick();
+        match self.map.get(key).copied() {
+            Some(id) => {
+                self.stats.hits += 1;
+                self.touch(id);
+                Some(id)
+            }
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    fn tick(&mut self) {
+        let Some(interval) = self.decay_interval else {
+            return;
+        };
+
+        self.ops += 1;
+        if self.ops >= interval.max(self.len() as u64) {
+            self.ops = 0;
+            self.decay();
+        }
+    }
+
+    fn touch(&mut self, id: usize) {
+        let freq = self.node(id).freq;
+        let lower = self.lists[&freq].lower;
+        self.unlink(id);
+        let after = if self.lists.contains(&freq) {
+            freq
+        } else {
+            lower
+        };
+
+        self.node_mut(id).freq = freq + 1;
+        self.link_back(id, after);
+    }
+
+    fn evict(&mut self) {
+        if let Some((key, value)) = self.pop_lfu() {
+            self.stats.evictions += 1;
+            if let Some(listener) = self.on_evict.as_mut() {
+                listener(key, value);
+            }
+        }
+    }
+
+    fn alloc(&mut self, node: Node<K, V>) -> usize {
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = Some(node);
+                id
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn remove_node(&mut self, id: usize) -> (K, V) {
+        self.unlink(id);
+
+        let node = self.nodes[id].take().expect("linked node must be occupied");
+        self.free.push(id);
+        self.map.remove(&node.key);
+        (node.key, node.value)
+    }
+
+    fn node(&self, id: usize) -> &Node<K, V> {
+        self.nodes[id]
+            .as_ref()
+            .expect("linked node must be occupied")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Node<K, V> {
+        self.nodes[id]
+            .as_mut()
+            .expect("linked node must be occupied")
+    }
+
+    fn unlink(&mut self, id: usize) {
+        let (freq, prev, next) = {
+            let node = self.node(id);
+            (node.freq, node.prev, node.next)
+        };
+
+        if prev == NIL && next == NIL {
+            self.remove_list(freq);
+            return;
+        }
+
+        let list = self.list_mut(freq);
+        if prev == NIL {
+            list.head = next;
+        }
+        if next == NIL {
+            list.tail = prev;
+        }
+
+        if prev != NIL {
+            self.node_mut(prev).next = next;
+        }
+        if next != NIL {
+            self.node_mut(next).prev = prev;
+        }
+    }
+
+    fn link_back(&mut self, id: usize, after: u64) {
+        let freq = self.node(id).freq;
+        let tail = match self.lists.get_mut(&freq) {
+            Some(list) => std::mem::replace(&mut list.tail, id),
+            None => {
+                self.insert_list(freq, id, after);
+                NIL
+            }
+        };
+
+        let node = self.node_mut(id);
+        node.prev = tail;
+        node.next = NIL;
+        if tail != NIL {
+            self.node_mut(tail).next = id;
+        }
+    }
+
+    fn insert_list(&mut self, freq: u64, id: usize, lower: u64) {
+        let higher = match lower {
+            NO_FREQ => std::mem::replace(&mut self.lowest, freq),
+            l => std::mem::replace(&mut self.list_mut(l).higher, freq),
+        };
+        if higher != NO_FREQ {
+            self.list_mut(higher).lower = freq;
+        }
+
+        let list = FreqList {
+            head: id,
+            tail: id,
+            lower,
+            higher,
+        };
+        self.lists.insert(freq, list);
+    }
+
+    fn remove_list(&mut self, freq: u64) {
+        let list = self.lists.remove(&freq).expect("frequency list must exist");
+        match list.lower {
+            NO_FREQ => self.lowest = list.higher,
+            l => self.list_mut(l).higher = list.higher,
+        }
+        if list.higher != NO_FREQ {
+            self.list_mut(list.higher).lower = list.lower;
+        }
+    }
+
+    fn list_mut(&mut self, freq: u64) -> &mut FreqList {
+        self.lists
+            .get_mut(&freq)
+            .expect("frequency list must exist")
+    }
+}
+
+impl<K, V> fmt::Debug for LfuCache<K, V>
+where
+    K: Eq + Hash + Clone + fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LfuCache")
+            .field("len", &self.len())
+            .field("capacity", &self.capacity)
+            .field("lowest_frequency", &self.lowest)
+            .field("stats", &self.stats)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::{LfuCache, Stats};
+
+    #[test]
+    fn new_cache_is_empty() {
+        let c = LfuCache::<i32, i32>::new(2);
+        assert!(c.is_empty());
+        assert_eq!(c.capacity(), 2);
+        assert_eq!(c.stats(), Stats::default());
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity must be positive")]
+    fn zero_capacity_panics() {
+        LfuCache::<i32, i32>::new(0);
+    }
+
+    #[test]
+    fn evicts_least_frequently_used() {
+        let mut c = LfuCache::new(2);
+        c.put("a", 1);
+        c.put("b", 2);
+        c.get("a");
+        c.get("a");
+        c.get("b");
+
+        c.put("c", 3);
+        assert!(c.contains("a"));
+        assert!(!c.contains("b"));
+        assert_eq!(c.frequency("a"), Some(3));
+        assert_eq!(c.frequency("c"), Some(1));
+    }
+
+    #[test]
+    fn ties_are_broken_by_recency() {
+        let mut c = LfuCache::new(3);
+        c.put("a", 1);
+        c.put("b", 2);
+        c.put("c", 3);
+        c.get("a");
+        c.get("b");
+
+        assert_eq!(c.pop_lfu(), Some(("c", 3)));
+        assert_eq!(c.pop_lfu(), Some(("a", 1)));
+        assert_eq!(c.pop_lfu(), Some(("b", 2)));
+        assert_eq!(c.pop_lfu(), None);
+    }
+
+    #[test]
+    fn new_entries_restart_min_frequency() {
+        let mut c = LfuCache::new(2);
+        c.put("a", 1);
+        c.get("a");
+        c.put("b", 2);
+        c.get("b");
+        c.get("b");
+
+        c.put("c", 3);
+        assert!(!c.contains("a"));
+
+        c.put("d", 4);
+        assert!(!c.contains("c"));
+        assert!(c.contains("b"));
+    }
+
+    #[test]
+    fn put_existing_key_replaces_and_counts_as_use() {
+        let mut c = LfuCache::new(2);
+        assert_eq!(c.put("a", 1), None);
+        assert_eq!(c.put("a", 10), Some(1));
+        assert_eq!(c.frequency("a"), Some(2));
+        assert_eq!(c.len(), 1);
+    }
+
+    #[test]
+    fn peek_does_not_change_frequency_or_stats() {
+        let mut c = LfuCache::new(2);
+        c.put("a", 1);
+        assert_eq!(c.peek("a"), Some(&1));
+        assert_eq!(c.frequency("a"), Some(1));
+        assert_eq!(c.stats(), Stats::default());
+    }
+
+    #[test]
+    fn get_mut_counts_as_use() {
+        let mut c = LfuCache::new(2);
+        c.put("a", 1);
+        *c.get_mut("a").unwrap() += 1;
+        assert_eq!(c.peek("a"), Some(&2));
+        assert_eq!(c.frequency("a"), Some(2));
+    }
+
+    #[test]
+    fn stats_count_hits_misses_and_evictions() {
+        let mut c = LfuCache::new(1);
+        c.put(1, 1);
+        c.get(&1);
+        c.get(&2);
+        c.put(2, 2);
+
+        assert_eq!(
+            c.stats(),
+            Stats {
+                hits: 1,
+                misses: 1,
+                evictions: 1
+            }
+        );
+
+        c.reset_stats();
+        assert_eq!(c.stats(), Stats::default());
+    }
+
+    #[test]
+    fn eviction_listener_receives_evicted_pairs() {
+        let evicted = Rc::new(RefCell::new(Vec::new()));
+        let sink = Rc::clone(&evicted);
+
+        let mut c = LfuCache::new(2);
+        c.set_on_evict(move |k, v| sink.borrow_mut().push((k, v)));
+        c.put(1, "one");
+        c.put(2, "two");
+        c.get(&2);
+        c.put(3, "three");
+        c.remove(&3);
+        c.put(4, "four");
+
+        assert_eq!(*evicted.borrow(), vec![(1, "one")]);
+    }
+
+    #[test]
+    fn remove_updates_min_frequency() {
+        let mut c = LfuCache::new(3);
+        c.put("a", 1);
+        c.put("b", 2);
+        c.get("b");
+
+        assert_eq!(c.remove("a"), Some(1));
+        assert_eq!(c.remove("a"), None);
+        assert_eq!(c.pop_lfu(), Some(("b", 2)));
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    fn frequency_lists_stay_ordered_after_removals() {
+        let mut c = LfuCache::new(4);
+        for (k, uses) in [("a", 0), ("b", 2), ("c", 4), ("d", 6)] {
+            c.put(k, uses);
+            for _ in 0..uses {
+                c.get(k);
+            }
+        }
+
+        c.remove("c");
+        c.remove("a");
+        assert_eq!(c.pop_lfu(), Some(("b", 2)));
+        assert_eq!(c.pop_lfu(), Some(("d", 6)));
+        assert_eq!(c.pop_lfu(), None);
+    }
+
+    #[test]
+    fn decay_halves_frequencies() {
+        let mut c = LfuCache::new(3);
+        c.put("a", 1);
+        for _ in 0..9 {
+            c.get("a");
+        }
+        c.put("b", 2);
+        c.get("b");
+        c.get("b");
+
+        c.decay();
+        assert_eq!(c.frequency("a"), Some(5));
+        assert_eq!(c.frequency("b"), Some(1));
+        assert_eq!(c.pop_lfu(), Some(("b", 2)));
+    }
+
+    #[test]
+    fn stale_hot_key_eventually_leaves_with_decay() {
+        let mut c = LfuCache::new(2);
+        c.set_decay_interval(Some(8));
+
+        c.put("old", 0);
+        for _ in 0..50 {
+            c.get("old");
+        }
+
+        for i in 0..200 {
+            c.put("new", i);
+            c.get("new");
+        }
+        c.put("other", 0);
+
+        assert!(!c.contains("old"));
+        assert!(c.contains("new"));
+    }
+
+    #[test]
+    fn decay_interval_stretches_to_cache_length() {
+        let mut c = LfuCache::new(64);
+        for k in 0..64 {
+            c.put(k, k);
+        }
+        c.set_decay_interval(Some(4));
+
+        for _ in 0..63 {
+            c.get(&0);
+        }
+        assert_eq!(c.frequency(&0), Some(64));
+
+        c.get(&0);
+        assert_eq!(c.frequency(&0), Some(33));
+    }
+
+    #[test]
+    fn without_decay_hot_key_stays_forever() {
+        let mut c = LfuCache::new(2);
+        c.put(-1, 0);
+        for _ in 0..50 {
+            c.get(&-1);
+        }
+        for i in 0..200 {
+            c.put(i, i);
+        }
+
+        assert!(c.contains(&-1));
+    }
+
+    #[test]
+    fn scan_does_not_flush_hot_keys() {
+        let mut c = LfuCache::new(4);
+        for k in 0..3 {
+            c.put(k, k);
+            c.get(&k);
+        }
+
+        for k in 100..1000 {
+            c.put(k, k);
+        }
+
+        assert!((0..3).all(|k| c.contains(&k)));
+        assert_eq!(c.len(), 4);
+        assert!(c.nodes.len() <= 4);
+    }
+
+    #[test]
+    fn clear_empties_cache() {
+        let mut c = LfuCache::new(2);
+        c.put(1, 1);
+        c.put(2, 2);
+        c.clear();
+
+        assert!(c.is_empty());
+        assert_eq!(c.pop_lfu(), None);
+        c.put(3, 3);
+        assert_eq!(c.frequency(&3), Some(1));
+    }
+}
@@ -0,0 +1,580 @@
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::Hash;
+use std::iter::FusedIterator;
+
+use hash_map::HashMap;
+
+const NIL: usize = usize::MAX;
+
+type Weigher<K, V> = Box<dyn Fn(&K, &V) -> usize>;
+type Leftover<K, V> = Option<(K, V)>;
+
+struct Node<K, V> {
+    key: K,
+    value: V,
+    weight: usize,
+    prev: usize,
+    next: usize,
+}
+
+pub struct LruCache<K, V> {
+    map: HashMap<K, usize>,
+    nodes: Vec<Option<Node<K, V>>>,
+    free: Vec<usize>,
+    head: usize,
+    tail: usize,
+    capacity: usize,
+    weight: usize,
+    weigher: Option<Weigher<K, V>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Displaced<K, V> {
+    pub replaced: Option<(K, V)>,
+    pub evicted: Vec<(K, V)>,
+    pub rejected: Option<(K, V)>,
+}
+
+pub struct Iter<'a, K, V> {
+    nodes: &'a [Option<Node<K, V>>],
+    next: usize,
+    remaining: usize,
+}
+
+impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
+    pub fn new(capacity: usize) -> Self {
+        Self::build(capacity, HashMap::with_capacity(capacity), None)
+    }
+
+    pub fn with_weigher<F>(capacity: usize, weigher: F) -> Self
+    where
+        F: Fn(&K, &V) -> usize + 'static,
+    {
+        Self::build(capacity, HashMap::new(), Some(Box::new(weigher)))
+    }
+
+    fn build(capacity: usize, map: HashMap<K, usize>, weigher: Option<Weigher<K, V>>) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        Self {
+            map,
+            nodes: Vec::new(),
+            free: Vec::new(),
+            head: NIL,
+            tail: NIL,
+            capacity,
+            weight: 0,
+            weigher,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn weight(&self) -> usize {
+        self.weight
+    }
+
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.contains(key)
+    }
+
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let id = *self.map.get(key)?;
+        Some(&self.node(id).value)
+    }
+
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let id = *self.map.get(key)?;
+        self.promote(id);
+        Some(&self.node(id).value)
+    }
+
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let id = *self.map.get(key)?;
+        self.promote(id);
+        Some(&mut self.node_mut(id).value)
+    }
+
+    pub fn put(&mut self, key: K, value: V) -> Option<(K, V)> {
+        let mut evicted = None;
+        let (replaced, rejected) = self.store(key, value, |pair| {
+            evicted.get_or_insert(pair);
+        });
+        rejected.or(replaced).or(evicted)
+    }
+
+    pub fn put_full(&mut self, key: K, value: V) -> Displaced<K, V> {
+        let mut evicted = Vec::new();
+        let (replaced, rejected) = self.store(key, value, |pair| evicted.push(pair));
+        Displaced {
+            replaced,
+            evicted,
+            rejected,
+        }
+    }
+
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        if self.head == NIL {
+            return None;
+        }
+        Some(self.remove_node(self.head))
+    }
+
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        let node = self.nodes.get(self.head)?.as_ref()?;
+        Some((&node.key, &node.value))
+    }
+
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let id = *self.map.get(key)?;
+        Some(self.remove_node(id).1)
+    }
+
+    pub fn resize(&mut self, capacity: usize) -> Vec<(K, V)> {
+        assert!(capacity > 0, "capacity must be positive");
+        self.capacity = capacity;
+        let mut evicted = Vec::new();
+        self.evict_to(capacity, |pair| evicted.push(pair));
+        evicted
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.nodes.clear();
+        self.free.clear();
+        self.head = NIL;
+        self.tail = NIL;
+        self.weight = 0;
+    }
+
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            nodes: &self.nodes,
+            next: self.tail,
+            remaining: self.len(),
+        }
+    }
+
+    fn weigh(&self, key: &K, value: &V) -> usize {
+        match &self.weigher {
+            Some(weigher) => weigher(key, value),
+            None => 1,
+        }
+    }
+
+    fn store<F>(&mut self, key: K, value: V, evict: F) -> (Leftover<K, V>, Leftover<K, V>)
+    where
+        F: FnMut((K, V)),
+    {
+        let weight = self.weigh(&key, &value);
+        let existing = self.map.get(&key).copied();
+        if weight > self.capacity {
+            let replaced = existing.map(|id| self.remove_node(id));
+            return (replaced, Some((key, value)));
+        }
+
+        match existing {
+            Some(id) => {
+                let node = self.node_mut(id);
+                let old_weight = std::mem::replace(&mut node.weight, weight);
+                let old_value = std::mem::replace(&mut node.value, value);
+                self.weight = self.weight - old_weight + weight;
+                self.promote(id);
+                self.evict_to(self.capacity, evict);
+                (Some((key, old_value)), None)
+            }
+            None => {
+                self.evict_to(self.capacity - weight, evict);
+                let id = self.alloc(Node {
+                    key: key.clone(),
+                    value,
+                    weight,
+                    prev: NIL,
+                    next: NIL,
+                });
+                self.map.insert(key, id);
+                self.link_back(id);
+                self.weight += weight;
+                (None, None)
+            }
+        }
+    }
+
+    fn evict_to<F: FnMut((K, V))>(&mut self, capacity: usize, mut evict: F) {
+        while self.weight > capacity {
+            match self.pop_lru() {
+                Some(pair) => evict(pair),
+                None => break,
+            }
+        }
+    }
+
+    fn alloc(&mut self, node: Node<K, V>) -> usize {
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = Some(node);
+                id
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn remove_node(&mut self, id: usize) -> (K, V) {
+        self.unlink(id);
+        let node = self.nodes[id].take().expect("linked node must be occupied");
+        self.free.push(id);
+        self.map.remove(&node.key);
+        self.weight -= node.weight;
+        (node.key, node.value)
+    }
+
+    fn node(&self, id: usize) -> &Node<K, V> {
+        self.nodes[id]
+            .as_ref()
+            .expect("linked node must be occupied")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Node<K, V> {
+        self.nodes[id]
+            .as_mut()
+            .expect("linked node must be occupied")
+    }
+
+    fn promote(&mut self, id: usize) {
+        if self.tail != id {
+            self.unlink(id);
+            self.link_back(id);
+        }
+    }
+
+    fn unlink(&mut self, id: usize) {
+        let (prev, next) = {
+            let node = self.node(id);
+            (node.prev, node.next)
+        };
+
+        match prev {
+            NIL => self.head = next,
+            p => self.node_mut(p).next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            n => self.node_mut(n).prev = prev,
+        }
+    }
+
+    fn link_back(&mut self, id: usize) {
+        let tail = self.tail;
+        let node = self.node_mut(id);
+        node.prev = tail;
+        node.next = NIL;
+
+        match tail {
+            NIL => self.head = id,
+            t => self.node_mut(t).next = id,
+        }
+        self.tail = id;
+    }
+}
+
+impl<K, V> fmt::Debug for LruCache<K, V>
+where
+    K: Eq + Hash + Clone + fmt::Debug,
+    V: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let node = self.nodes[self.next].as_ref()?;
+        self.next = node.prev;
+        self.remaining -= 1;
+        Some((&node.key, &node.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+impl<K, V> FusedIterator for Iter<'_, K, V> {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Displaced, LruCache};
+
+    fn keys(cache: &LruCache<&'static str, i32>) -> Vec<&'static str> {
+        cache.iter().map(|(k, _)| *k).collect()
+    }
+
+    #[test]
+    fn new_cache_is_empty() {
+        let c = LruCache::<i32, i32>::new(2);
+        assert!(c.is_empty());
+        assert_eq!(c.len(), 0);
+        assert_eq!(c.capacity(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "capacity must be positive")]
+    fn zero_capacity_panics() {
+        LruCache::<i32, i32>::new(0);
+    }
+
+    #[test]
+    fn put_evicts_least_recently_used() {
+        let mut c = LruCache::new(2);
+        assert_eq!(c.put("a", 1), None);
+        assert_eq!(c.put("b", 2), None);
+        assert_eq!(c.put("c", 3), Some(("a", 1)));
+
+        assert!(!c.contains("a"));
+        assert_eq!(c.len(), 2);
+        assert_eq!(keys(&c), ["c", "b"]);
+    }
+
+    #[test]
+    fn get_promotes_entry() {
+        let mut c = LruCache::new(2);
+        c.put("a", 1);
+        c.put("b", 2);
+
+        assert_eq!(c.get("a"), Some(&1));
+        assert_eq!(c.put("c", 3), Some(("b", 2)));
+        assert_eq!(keys(&c), ["c", "a"]);
+    }
+
+    #[test]
+    fn peek_does_not_promote() {
+        let mut c = LruCache::new(2);
+        c.put("a", 1);
+        c.put("b", 2);
+
+        assert_eq!(c.peek("a"), Some(&1));
+        assert_eq!(c.put("c", 3), Some(("a", 1)));
+    }
+
+    #[test]
+    fn put_existing_key_replaces_and_promotes() {
+        let mut c = LruCache::new(2);
+        c.put("a", 1);
+        c.put("b", 2);
+
+        assert_eq!(c.put("a", 10), Some(("a", 1)));
+        assert_eq!(c.len(), 2);
+        assert_eq!(keys(&c), ["a", "b"]);
+        assert_eq!(c.peek_lru(), Some((&"b", &2)));
+    }
+
+    #[test]
+    fn get_mut_updates_and_promotes() {
+        let mut c = LruCache::new(2);
+        c.put("a", 1);
+        c.put("b", 2);
+
+        *c.get_mut("a").unwrap() += 1;
+        assert_eq!(keys(&c), ["a", "b"]);
+        assert_eq!(c.peek("a"), Some(&2));
+    }
+
+    #[test]
+    fn pop_lru_returns_oldest_first() {
+        let mut c = LruCache::new(3);
+        c.put("a", 1);
+        c.put("b", 2);
+        c.put("c", 3);
+
+        assert_eq!(c.pop_lru(), Some(("a", 1)));
+        assert_eq!(c.pop_lru(), Some(("b", 2)));
+        assert_eq!(c.pop_lru(), Some(("c", 3)));
+        assert_eq!(c.pop_lru(), None);
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    fn remove_unlinks_entry() {
+        let mut c = LruCache::new(3);
+        c.put("a", 1);
+        c.put("b", 2);
+        c.put("c", 3);
+
+        assert_eq!(c.remove("b"), Some(2));
+        assert_eq!(c.remove("b"), None);
+        assert_eq!(keys(&c), ["c", "a"]);
+    }
+
+    #[test]
+    fn resize_shrinks_by_evicting_lru() {
+        let mut c = LruCache::new(4);
+        for (i, k) in ["a", "b", "c", "d"].into_iter().enumerate() {
+            c.put(k, i as i32);
+        }
+
+        assert_eq!(c.resize(2), vec![("a", 0), ("b", 1)]);
+        assert_eq!(c.capacity(), 2);
+        assert_eq!(keys(&c), ["d", "c"]);
+
+        assert!(c.resize(3).is_empty());
+        assert_eq!(c.put("e", 4), None);
+        assert_eq!(c.len(), 3);
+    }
+
+    #[test]
+    fn weigher_bounds_total_weight() {
+        let mut c = LruCache::with_weigher(10, |_: &i32, v: &String| v.len());
+        c.put(1, "aaaa".to_string());
+        c.put(2, "bbbb".to_string());
+        assert_eq!(c.weight(), 8);
+
+        let displaced = c.put_full(3, "cccccc".to_string());
+        assert_eq!(displaced.evicted, [(1, "aaaa".to_string())]);
+        assert_eq!(c.weight(), 10);
+
+        let displaced = c.put_full(4, "dddddddd".to_string());
+        assert_eq!(displaced.evicted.len(), 2);
+        assert_eq!(displaced.replaced, None);
+        assert_eq!(c.len(), 1);
+        assert_eq!(c.weight(), 8);
+    }
+
+    #[test]
+    fn weigher_rejects_entry_heavier_than_capacity() {
+        let mut c = LruCache::with_weigher(4, |_: &i32, v: &String| v.len());
+        c.put(1, "aa".to_string());
+
+        let displaced = c.put_full(2, "too heavy".to_string());
+        assert_eq!(
+            displaced,
+            Displaced {
+                replaced: None,
+                evicted: vec![],
+                rejected: Some((2, "too heavy".to_string())),
+            }
+        );
+        assert!(c.contains(&1));
+        assert!(!c.contains(&2));
+        assert_eq!(c.weight(), 2);
+
+        assert_eq!(
+            c.put(1, "heavier".to_string()),
+            Some((1, "heavier".to_string()))
+        );
+        assert!(c.is_empty());
+    }
+
+    #[test]
+    fn put_full_separates_replaced_and_evicted_pairs() {
+        let mut c = LruCache::with_weigher(6, |_: &&str, v: &i32| *v as usize);
+        c.put("a", 2);
+        c.put("b", 2);
+        c.put("c", 2);
+
+        let displaced = c.put_full("c", 5);
+        assert_eq!(displaced.replaced, Some(("c", 2)));
+        assert_eq!(displaced.evicted, [("a", 2), ("b", 2)]);
+        assert_eq!(displaced.rejected, None);
+        assert_eq!(keys(&c), ["c"]);
+    }
+
+    #[test]
+    fn put_returns_least_recent_of_several_evictions() {
+        let mut c = LruCache::with_weigher(6, |_: &&str, v: &i32| *v as usize);
+        c.put("a", 2);
+        c.put("b", 2);
+        c.put("c", 2);
+
+        assert_eq!(c.put("d", 5), Some(("a", 2)));
+        assert_eq!(keys(&c), ["d"]);
+    }
+
+    #[test]
+    fn weigher_updates_weight_when_value_is_replaced() {
+        let mut c = LruCache::with_weigher(10, |_: &i32, v: &String| v.len());
+        c.put(1, "a".to_string());
+        c.put(2, "bbb".to_string());
+        c.put(1, "aaaaaaa".to_string());
+
+        assert_eq!(c.weight(), 10);
+        assert_eq!(c.len(), 2);
+    }
+
+    #[test]
+    fn slots_are_reused_after_eviction() {
+        let mut c = LruCache::new(8);
+        for i in 0..10_000 {
+            c.put(i, i);
+        }
+
+        assert_eq!(c.len(), 8);
+        assert!(c.nodes.len() <= 8);
+        assert!((9_992..10_000).all(|i| c.contains(&i)));
+    }
+
+    #[test]
+    fn memoizes_expensive_calls() {
+        let mut cache = LruCache::new(16);
+        let mut misses = 0;
+
+        for n in (0..64).chain(48..64) {
+            if cache.get(&n).is_none() {
+                misses += 1;
+                cache.put(n, n * n);
+            }
+        }
+
+        assert_eq!(misses, 64);
+        assert_eq!(cache.peek(&63), Some(&3969));
+    }
+
+    #[test]
+    fn debug_lists_most_recent_first() {
+        let mut c = LruCache::new(2);
+        c.put("a", 1);
+        c.put("b", 2);
+        assert_eq!(format!("{c:?}"), r#"{"b": 2, "a": 1}"#);
+    }
+}